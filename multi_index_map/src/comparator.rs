@@ -0,0 +1,146 @@
+//! Custom orders for the keys of ordered indexes, declared with eg.
+//! `#[multi_index(ordered_unique, order = "desc")]` or `#[multi_index(ordered_unique, compare = "path::to::cmp")]`.
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A total order on keys of type `K`, used by an ordered index in place of the `Ord` impl of `K`.
+///
+/// For `compare = "path::to::cmp"`, an implementation calling the given function is generated by the derive macro.
+pub trait KeyOrder<K: ?Sized> {
+    fn cmp(a: &K, b: &K) -> Ordering;
+}
+
+/// The reverse of the `Ord` impl of the key, declared with `order = "desc"`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Descending;
+
+impl<K: Ord + ?Sized> KeyOrder<K> for Descending {
+    fn cmp(a: &K, b: &K) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+/// Key of the lookup table of an ordered index with a custom order `C`, whose `Ord` impl follows `C` rather than
+///   that of `K`.
+/// This is an implementation detail of the generated map, the accessors take and return keys of type `K`.
+#[doc(hidden)]
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize), serde(transparent))]
+pub struct Compared<C, K: ?Sized> {
+    _order: PhantomData<fn() -> C>,
+    key: K,
+}
+
+impl<C, K> Compared<C, K> {
+    pub fn new(key: K) -> Self {
+        Self {
+            _order: PhantomData,
+            key,
+        }
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+}
+
+impl<C, K: ?Sized> Compared<C, K> {
+    pub fn from_ref(key: &K) -> &Self {
+        // Safety: Compared is repr(transparent) over K, as PhantomData is zero-sized.
+        unsafe { &*(key as *const K as *const Self) }
+    }
+
+    pub fn as_key(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<C: KeyOrder<K>, K: ?Sized> Ord for Compared<C, K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        C::cmp(&self.key, &other.key)
+    }
+}
+
+impl<C: KeyOrder<K>, K: ?Sized> PartialOrd for Compared<C, K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C: KeyOrder<K>, K: ?Sized> PartialEq for Compared<C, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<C: KeyOrder<K>, K: ?Sized> Eq for Compared<C, K> {}
+
+impl<C, K: Clone> Clone for Compared<C, K> {
+    fn clone(&self) -> Self {
+        Self::new(self.key.clone())
+    }
+}
+
+impl<C, K: core::fmt::Debug + ?Sized> core::fmt::Debug for Compared<C, K> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.key.fmt(f)
+    }
+}
+
+// Every Compared borrows as itself through the blanket impl, so each borrowed form is listed separately.
+impl<C> Borrow<Compared<C, str>> for Compared<C, String> {
+    fn borrow(&self) -> &Compared<C, str> {
+        Compared::from_ref(self.key.as_str())
+    }
+}
+
+impl<C, T> Borrow<Compared<C, [T]>> for Compared<C, Vec<T>> {
+    fn borrow(&self) -> &Compared<C, [T]> {
+        Compared::from_ref(self.key.as_slice())
+    }
+}
+
+impl<C, T: ?Sized> Borrow<Compared<C, T>> for Compared<C, Box<T>> {
+    fn borrow(&self) -> &Compared<C, T> {
+        Compared::from_ref(&*self.key)
+    }
+}
+
+/// The bounds of a range of keys, wrapped for a lookup table keyed on `Compared<C, K>`.
+#[doc(hidden)]
+pub type ComparedBounds<'a, C, Q> = (Bound<&'a Compared<C, Q>>, Bound<&'a Compared<C, Q>>);
+
+/// Wrap the bounds of a range of keys, so that it can query a lookup table keyed on `Compared<C, K>`.
+/// The bounds are in the order of `C`. If the start bound is after the end bound, empty bounds are returned
+///   rather than panicking in `BTreeMap::range`.
+#[doc(hidden)]
+pub fn wrap_bounds<C, Q: ?Sized>(range: &impl RangeBounds<Q>) -> ComparedBounds<'_, C, Q>
+where
+    Compared<C, Q>: Ord,
+{
+    crate::non_inverted(wrap(range.start_bound()), wrap(range.end_bound()))
+}
+
+/// As `wrap_bounds`, but for a descending order `C`, whose ranges are given in ascending order of the keys.
+/// The bounds are swapped, so that the start bound is the greater key in the order of `C`.
+#[doc(hidden)]
+pub fn wrap_reversed_bounds<C, Q: ?Sized>(range: &impl RangeBounds<Q>) -> ComparedBounds<'_, C, Q>
+where
+    Compared<C, Q>: Ord,
+{
+    crate::non_inverted(wrap(range.end_bound()), wrap(range.start_bound()))
+}
+
+fn wrap<C, Q: ?Sized>(bound: Bound<&Q>) -> Bound<&Compared<C, Q>> {
+    match bound {
+        Bound::Included(key) => Bound::Included(Compared::from_ref(key)),
+        Bound::Excluded(key) => Bound::Excluded(Compared::from_ref(key)),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
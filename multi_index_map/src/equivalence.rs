@@ -0,0 +1,120 @@
+//! Custom equivalences for the keys of hashed indexes, declared with eg.
+//! `#[multi_index(hashed_unique, eq = "ascii_case_insensitive")]`.
+
+use std::borrow::Borrow;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// An equivalence relation on keys of type `K`, used by a hashed index in place of the `Hash` and `Eq` impls of `K`.
+/// Declared on an index with eg. `#[multi_index(hashed_unique, eq = "path::to::Equivalence")]`.
+///
+/// As with `Hash` and `Eq`, keys which are equivalent must have equal hashes.
+/// To allow lookups by a borrowed form of the key, eg. `&str` for `String`, implement it for both types,
+///   such that the hash and equivalence of a key and its borrowed form agree.
+pub trait KeyEquivalence<K: ?Sized> {
+    fn hash<H: Hasher>(key: &K, state: &mut H);
+
+    fn eq(a: &K, b: &K) -> bool;
+}
+
+/// Equivalence of strings ignoring ASCII case, declared with `eq = "ascii_case_insensitive"`.
+/// Implemented for any key which is `AsRef<str>`, so an index on a `String` field can be queried by `&str`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AsciiCaseInsensitive;
+
+impl<K: AsRef<str> + ?Sized> KeyEquivalence<K> for AsciiCaseInsensitive {
+    fn hash<H: Hasher>(key: &K, state: &mut H) {
+        for byte in key.as_ref().bytes() {
+            state.write_u8(byte.to_ascii_lowercase());
+        }
+        // Terminate the key, as the Hash impl of str does, so that tuples of keys hash differently.
+        state.write_u8(0xff);
+    }
+
+    fn eq(a: &K, b: &K) -> bool {
+        a.as_ref().eq_ignore_ascii_case(b.as_ref())
+    }
+}
+
+/// Key of the lookup table of a hashed index with a custom equivalence `E`, whose `Hash` and `Eq` impls follow `E`
+///   rather than those of `K`.
+/// This is an implementation detail of the generated map, the accessors take and return keys of type `K`.
+#[doc(hidden)]
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize), serde(transparent))]
+pub struct Equivalent<E, K: ?Sized> {
+    _equivalence: PhantomData<fn() -> E>,
+    key: K,
+}
+
+impl<E, K> Equivalent<E, K> {
+    pub fn new(key: K) -> Self {
+        Self {
+            _equivalence: PhantomData,
+            key,
+        }
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+}
+
+impl<E, K: ?Sized> Equivalent<E, K> {
+    pub fn from_ref(key: &K) -> &Self {
+        // Safety: Equivalent is repr(transparent) over K, as PhantomData is zero-sized.
+        unsafe { &*(key as *const K as *const Self) }
+    }
+
+    pub fn as_key(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<E: KeyEquivalence<K>, K: ?Sized> Hash for Equivalent<E, K> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        E::hash(&self.key, state);
+    }
+}
+
+impl<E: KeyEquivalence<K>, K: ?Sized> PartialEq for Equivalent<E, K> {
+    fn eq(&self, other: &Self) -> bool {
+        E::eq(&self.key, &other.key)
+    }
+}
+
+impl<E: KeyEquivalence<K>, K: ?Sized> Eq for Equivalent<E, K> {}
+
+impl<E, K: Clone> Clone for Equivalent<E, K> {
+    fn clone(&self) -> Self {
+        Self::new(self.key.clone())
+    }
+}
+
+impl<E, K: core::fmt::Debug + ?Sized> core::fmt::Debug for Equivalent<E, K> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.key.fmt(f)
+    }
+}
+
+// Every Equivalent borrows as itself through the blanket impl, so each borrowed form is listed separately.
+impl<E> Borrow<Equivalent<E, str>> for Equivalent<E, String> {
+    fn borrow(&self) -> &Equivalent<E, str> {
+        Equivalent::from_ref(self.key.as_str())
+    }
+}
+
+impl<E, T> Borrow<Equivalent<E, [T]>> for Equivalent<E, Vec<T>> {
+    fn borrow(&self) -> &Equivalent<E, [T]> {
+        Equivalent::from_ref(self.key.as_slice())
+    }
+}
+
+impl<E, T: ?Sized> Borrow<Equivalent<E, T>> for Equivalent<E, Box<T>> {
+    fn borrow(&self) -> &Equivalent<E, T> {
+        Equivalent::from_ref(&*self.key)
+    }
+}
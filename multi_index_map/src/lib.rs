@@ -1,20 +1,163 @@
+pub use comparator::{Descending, KeyOrder};
+pub use equivalence::{AsciiCaseInsensitive, KeyEquivalence};
 pub use multi_index_map_derive::MultiIndexMap;
 
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
+
+// Used by the generated maps, but not part of the public API.
+#[doc(hidden)]
+pub use comparator::Compared;
+#[doc(hidden)]
+pub use equivalence::Equivalent;
+
+pub mod comparator;
+pub mod equivalence;
+#[doc(hidden)]
+pub mod random_access;
+#[doc(hidden)]
+pub mod rank_tree;
+#[doc(hidden)]
+pub mod sequence;
+
+/// Error returned by `try_insert` when inserting an element would violate a uniqueness constraint.
+/// The map is left unchanged, and the rejected element is handed back.
 #[derive(Clone, Copy, PartialEq, Eq)]
-pub struct UniquenessError<T>(pub T);
+pub struct UniquenessError<T, K = usize> {
+    /// The element which could not be inserted.
+    pub elem: T,
+    /// The name of the unique index whose constraint would have been violated.
+    pub index: &'static str,
+    /// The key handle of the element already in the map with the same value in that index.
+    pub existing: K,
+}
 
-impl<T> core::fmt::Display for UniquenessError<T> {
+impl<T, K> core::fmt::Display for UniquenessError<T, K> {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(
             f,
-            "Unable to insert element, uniqueness constraint violated"
+            "Unable to insert element, uniqueness constraint violated on index '{}'",
+            self.index
         )
     }
 }
 
-impl<T> core::fmt::Debug for UniquenessError<T> {
+impl<T, K: core::fmt::Debug> core::fmt::Debug for UniquenessError<T, K> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_tuple("UniquenessViolated").finish()
+        f.debug_struct("UniquenessError")
+            .field("index", &self.index)
+            .field("existing", &self.existing)
+            .finish()
+    }
+}
+
+impl<T, K: core::fmt::Debug> std::error::Error for UniquenessError<T, K> {}
+
+/// Error returned by the `try_modify_by_` methods when a modification would violate a uniqueness constraint.
+/// The modified elements are restored to their previous state, and all lookup tables are left unchanged.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ModifyError<K = usize> {
+    /// The name of the unique index whose constraint would have been violated.
+    pub index: &'static str,
+    /// The key handle of the element already in the map with the same value in that index.
+    pub existing: K,
+}
+
+impl<K> core::fmt::Display for ModifyError<K> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "Unable to modify element, uniqueness constraint violated on index '{}'",
+            self.index
+        )
+    }
+}
+
+impl<K: core::fmt::Debug> core::fmt::Debug for ModifyError<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModifyError")
+            .field("index", &self.index)
+            .field("existing", &self.existing)
+            .finish()
+    }
+}
+
+impl<K: core::fmt::Debug> std::error::Error for ModifyError<K> {}
+
+/// Error returned by `insert` on a vacant entry when the element cannot be inserted.
+/// The map is left unchanged, and the rejected element is handed back.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum VacantEntryError<T, K = usize> {
+    /// The element does not have the key the entry was created with.
+    KeyMismatch(T),
+    /// Inserting the element would violate a uniqueness constraint in another index.
+    Uniqueness(UniquenessError<T, K>),
+}
+
+impl<T, K> VacantEntryError<T, K> {
+    /// The element which could not be inserted.
+    pub fn into_elem(self) -> T {
+        match self {
+            Self::KeyMismatch(elem) => elem,
+            Self::Uniqueness(err) => err.elem,
+        }
+    }
+}
+
+impl<T, K> From<UniquenessError<T, K>> for VacantEntryError<T, K> {
+    fn from(err: UniquenessError<T, K>) -> Self {
+        Self::Uniqueness(err)
+    }
+}
+
+impl<T, K> core::fmt::Display for VacantEntryError<T, K> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::KeyMismatch(_) => write!(
+                f,
+                "Unable to insert element through entry, element key does not match entry key"
+            ),
+            Self::Uniqueness(err) => core::fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+impl<T, K: core::fmt::Debug> core::fmt::Debug for VacantEntryError<T, K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KeyMismatch(_) => f.write_str("KeyMismatch"),
+            Self::Uniqueness(err) => f.debug_tuple("Uniqueness").field(err).finish(),
+        }
+    }
+}
+
+impl<T, K: core::fmt::Debug> std::error::Error for VacantEntryError<T, K> {}
+
+/// The bounds of a range of keys, for querying a lookup table keyed on `Q`.
+/// If the start bound is after the end bound, empty bounds are returned rather than panicking in `BTreeMap::range`.
+#[doc(hidden)]
+pub fn range_bounds<Q: Ord + ?Sized>(range: &impl RangeBounds<Q>) -> (Bound<&Q>, Bound<&Q>) {
+    non_inverted(range.start_bound(), range.end_bound())
+}
+
+// BTreeMap::range panics if the start bound is after the end bound, or if they are equal and both excluded,
+//   so such bounds are replaced by bounds which are empty but valid.
+pub(crate) fn non_inverted<'a, T: Ord + ?Sized>(
+    start: Bound<&'a T>,
+    end: Bound<&'a T>,
+) -> (Bound<&'a T>, Bound<&'a T>) {
+    let (start_key, end_key) = match (start, end) {
+        (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e)) => {
+            (s, e)
+        }
+        _ => return (start, end),
+    };
+    match start_key.cmp(end_key) {
+        Ordering::Greater => (Bound::Included(start_key), Bound::Excluded(start_key)),
+        Ordering::Equal if matches!((start, end), (Bound::Excluded(_), Bound::Excluded(_))) => {
+            (Bound::Included(start_key), Bound::Excluded(start_key))
+        }
+        _ => (start, end),
     }
 }
 
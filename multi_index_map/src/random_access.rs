@@ -0,0 +1,76 @@
+//! Dense sequence of the positions of elements in the backing storage,
+//! used by maps with a `#[multi_index(random_access)]` index to access elements by their position in O(1).
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Sequence of positions in the backing storage of a map, without any holes.
+/// `order` holds the backing storage positions in sequence order, and `offsets` holds the inverse,
+///   ie. the offset in `order` of each backing storage position, so both lookups are constant-time.
+/// Inserting or removing anywhere other than the back shifts every later offset, so is linear-time.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct RandomAccess {
+    order: Vec<usize>,
+    offsets: Vec<usize>,
+}
+
+impl RandomAccess {
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Get the backing storage position at offset `n` in the sequence.
+    pub fn nth(&self, n: usize) -> Option<usize> {
+        self.order.get(n).copied()
+    }
+
+    /// Get the offset in the sequence of a backing storage position which is in the sequence.
+    pub fn offset_of(&self, idx: usize) -> usize {
+        self.offsets[idx]
+    }
+
+    /// Append a backing storage position which is not yet in the sequence.
+    pub fn push(&mut self, idx: usize) {
+        if idx >= self.offsets.len() {
+            self.offsets.resize(idx + 1, 0);
+        }
+        self.offsets[idx] = self.order.len();
+        self.order.push(idx);
+    }
+
+    /// Remove a backing storage position which is in the sequence, shifting every later position down by one.
+    pub fn remove(&mut self, idx: usize) {
+        let offset = self.offsets[idx];
+        self.order.remove(offset);
+        self.reindex_from(offset);
+    }
+
+    /// Move a backing storage position which is in the sequence to offset `n`, shifting the positions in between.
+    /// Panics if `n` is not a valid offset.
+    pub fn move_to(&mut self, idx: usize, n: usize) {
+        let offset = self.offsets[idx];
+        self.order.remove(offset);
+        self.order.insert(n, idx);
+        self.reindex_from(offset.min(n));
+    }
+
+    pub fn clear(&mut self) {
+        self.order.clear();
+        self.offsets.clear();
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, usize> {
+        self.order.iter()
+    }
+
+    fn reindex_from(&mut self, start: usize) {
+        for (offset, &idx) in self.order.iter().enumerate().skip(start) {
+            self.offsets[idx] = offset;
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Order-statistic tree over the keys of an index and the positions of their elements in the backing storage,
+//! used by `ordered_ranked` indexes alongside their BTreeMap to answer rank queries in logarithmic time.
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+type Link<K> = Option<Box<Node<K>>>;
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+struct Node<K> {
+    key: K,
+    idx: usize,
+    priority: u64,
+    // The number of entries in the subtree rooted at this node, including itself.
+    size: usize,
+    left: Link<K>,
+    right: Link<K>,
+}
+
+impl<K> Node<K> {
+    fn update_size(&mut self) {
+        self.size = 1 + size(&self.left) + size(&self.right);
+    }
+}
+
+fn size<K>(link: &Link<K>) -> usize {
+    link.as_ref().map_or(0, |node| node.size)
+}
+
+/// Treap of `(key, idx)` entries, ordered by key, then by position in the backing storage.
+/// Each subtree tracks its size, so the rank of a key and the entry at a given rank are found in a single descent.
+/// Priorities are drawn from a deterministic generator, so the shape of the tree is reproducible.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct RankTree<K> {
+    root: Link<K>,
+    seed: u64,
+}
+
+impl<K> Default for RankTree<K> {
+    fn default() -> Self {
+        Self {
+            root: None,
+            seed: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+}
+
+impl<K: Ord> RankTree<K> {
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn clear(&mut self) {
+        self.root = None;
+    }
+
+    /// Insert an entry which is not yet in the tree.
+    pub fn insert(&mut self, key: K, idx: usize) {
+        let priority = self.next_priority();
+        let (left, right) = split(self.root.take(), &|k, i| (k, i) < (&key, idx));
+        let node = Box::new(Node {
+            key,
+            idx,
+            priority,
+            size: 1,
+            left: None,
+            right: None,
+        });
+        self.root = merge(merge(left, Some(node)), right);
+    }
+
+    /// Remove an entry which is in the tree.
+    pub fn remove(&mut self, key: &K, idx: usize) {
+        let (left, rest) = split(self.root.take(), &|k, i| (k, i) < (key, idx));
+        let (_removed, right) = split(rest, &|k, i| (k, i) <= (key, idx));
+        self.root = merge(left, right);
+    }
+
+    /// Count the entries whose key is strictly less than `key`.
+    pub fn rank<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.count_while(|k| k.borrow() < key)
+    }
+
+    /// Get the position in the backing storage of the entry with rank `n`, counting from zero.
+    pub fn nth(&self, mut n: usize) -> Option<usize> {
+        let mut link = &self.root;
+        while let Some(node) = link {
+            let left_size = size(&node.left);
+            match n.cmp(&left_size) {
+                Ordering::Less => link = &node.left,
+                Ordering::Equal => return Some(node.idx),
+                Ordering::Greater => {
+                    n -= left_size + 1;
+                    link = &node.right;
+                }
+            }
+        }
+        None
+    }
+
+    /// Count the entries whose key falls in the given range.
+    pub fn count_range<Q, R>(&self, range: R) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let lower = match range.start_bound() {
+            Bound::Included(start) => self.count_while(|k| k.borrow() < start),
+            Bound::Excluded(start) => self.count_while(|k| k.borrow() <= start),
+            Bound::Unbounded => 0,
+        };
+        let upper = match range.end_bound() {
+            Bound::Included(end) => self.count_while(|k| k.borrow() <= end),
+            Bound::Excluded(end) => self.count_while(|k| k.borrow() < end),
+            Bound::Unbounded => self.len(),
+        };
+        upper.saturating_sub(lower)
+    }
+
+    // Count the entries whose key satisfies `pred`, which must hold for some prefix of the entries.
+    fn count_while(&self, pred: impl Fn(&K) -> bool) -> usize {
+        let mut count = 0;
+        let mut link = &self.root;
+        while let Some(node) = link {
+            if pred(&node.key) {
+                count += size(&node.left) + 1;
+                link = &node.right;
+            } else {
+                link = &node.left;
+            }
+        }
+        count
+    }
+
+    // splitmix64, which is enough to keep the treap balanced in expectation.
+    fn next_priority(&mut self) -> u64 {
+        self.seed = self.seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+// Split a subtree into the entries satisfying `is_left`, which must hold for some prefix of the entries,
+//   and the rest.
+fn split<K>(link: Link<K>, is_left: &impl Fn(&K, usize) -> bool) -> (Link<K>, Link<K>) {
+    let Some(mut node) = link else {
+        return (None, None);
+    };
+    if is_left(&node.key, node.idx) {
+        let (left, right) = split(node.right.take(), is_left);
+        node.right = left;
+        node.update_size();
+        (Some(node), right)
+    } else {
+        let (left, right) = split(node.left.take(), is_left);
+        node.left = right;
+        node.update_size();
+        (left, Some(node))
+    }
+}
+
+// Merge two subtrees, where every entry in `left` is ordered before every entry in `right`.
+fn merge<K>(left: Link<K>, right: Link<K>) -> Link<K> {
+    match (left, right) {
+        (None, right) => right,
+        (left, None) => left,
+        (Some(mut left), Some(mut right)) => {
+            if left.priority > right.priority {
+                left.right = merge(left.right.take(), Some(right));
+                left.update_size();
+                Some(left)
+            } else {
+                right.left = merge(Some(left), right.left.take());
+                right.update_size();
+                Some(right)
+            }
+        }
+    }
+}
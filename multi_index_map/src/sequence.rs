@@ -0,0 +1,172 @@
+//! Doubly linked list over the positions of elements in the backing storage,
+//! used by maps with a `#[multi_index(sequenced)]` index to keep their elements in insertion order.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// Marks the absence of a previous or next position, ie. the ends of the sequence.
+const NIL: usize = usize::MAX;
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+struct Link {
+    prev: usize,
+    next: usize,
+}
+
+/// Sequence of positions in the backing storage of a map.
+/// The links of each position are stored at that position, so linking and unlinking are constant-time.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Sequence {
+    links: Vec<Link>,
+    head: usize,
+    tail: usize,
+    len: usize,
+}
+
+impl Default for Sequence {
+    fn default() -> Self {
+        Self {
+            links: Vec::new(),
+            head: NIL,
+            tail: NIL,
+            len: 0,
+        }
+    }
+}
+
+impl Sequence {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn front(&self) -> Option<usize> {
+        (self.head != NIL).then_some(self.head)
+    }
+
+    pub fn back(&self) -> Option<usize> {
+        (self.tail != NIL).then_some(self.tail)
+    }
+
+    /// Link a position which is not yet in the sequence at the back.
+    pub fn push_back(&mut self, idx: usize) {
+        self.link_before(idx, NIL);
+    }
+
+    /// Link a position which is not yet in the sequence at the front.
+    pub fn push_front(&mut self, idx: usize) {
+        self.link_before(idx, self.head);
+    }
+
+    /// Unlink a position which is in the sequence.
+    pub fn remove(&mut self, idx: usize) {
+        let Link { prev, next } = self.links[idx];
+        if prev == NIL {
+            self.head = next;
+        } else {
+            self.links[prev].next = next;
+        }
+        if next == NIL {
+            self.tail = prev;
+        } else {
+            self.links[next].prev = prev;
+        }
+        self.len -= 1;
+    }
+
+    /// Move a position which is in the sequence to just before `before`, or to the back if `before` is None.
+    pub fn relocate(&mut self, idx: usize, before: Option<usize>) {
+        if before == Some(idx) {
+            return;
+        }
+        self.remove(idx);
+        self.link_before(idx, before.unwrap_or(NIL));
+    }
+
+    pub fn clear(&mut self) {
+        self.links.clear();
+        self.head = NIL;
+        self.tail = NIL;
+        self.len = 0;
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            links: &self.links,
+            front: self.head,
+            back: self.tail,
+            remaining: self.len,
+        }
+    }
+
+    fn link_before(&mut self, idx: usize, next: usize) {
+        if idx >= self.links.len() {
+            self.links.resize(idx + 1, Link { prev: NIL, next: NIL });
+        }
+        let prev = if next == NIL {
+            self.tail
+        } else {
+            self.links[next].prev
+        };
+        self.links[idx] = Link { prev, next };
+        if prev == NIL {
+            self.head = idx;
+        } else {
+            self.links[prev].next = idx;
+        }
+        if next == NIL {
+            self.tail = idx;
+        } else {
+            self.links[next].prev = idx;
+        }
+        self.len += 1;
+    }
+}
+
+/// Iterator over the positions in a [`Sequence`], from front to back.
+#[derive(Clone)]
+pub struct Iter<'a> {
+    links: &'a [Link],
+    front: usize,
+    back: usize,
+    remaining: usize,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let idx = self.front;
+        self.front = self.links[idx].next;
+        self.remaining -= 1;
+        Some(idx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let idx = self.back;
+        self.back = self.links[idx].prev;
+        self.remaining -= 1;
+        Some(idx)
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl std::iter::FusedIterator for Iter<'_> {}
@@ -0,0 +1,211 @@
+use std::cmp::Ordering;
+use std::ops::Bound;
+
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, Debug, PartialEq)]
+#[multi_index_derive(Debug, Clone)]
+struct Bid {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    #[multi_index(ordered_non_unique, order = "desc")]
+    price: u64,
+    #[multi_index(ordered_ranked, order = "desc")]
+    quantity: u32,
+}
+
+fn ids<'a>(bids: impl Iterator<Item = &'a Bid>) -> Vec<u32> {
+    bids.map(|b| b.id).collect()
+}
+
+#[test]
+fn test_iter_in_descending_order() {
+    let mut book = MultiIndexBidMap::default();
+    for (id, price, quantity) in [(1, 100, 5), (2, 102, 1), (3, 99, 7), (4, 102, 3)] {
+        book.insert(Bid {
+            id,
+            price,
+            quantity,
+        });
+    }
+
+    assert_eq!(ids(book.iter_by_price()), [2, 4, 1, 3]);
+    assert_eq!(book.first_by_price().unwrap().price, 102);
+    assert_eq!(book.last_by_price().unwrap().price, 99);
+    assert_eq!(
+        book.keys_by_price().copied().collect::<Vec<_>>(),
+        [102, 100, 99]
+    );
+    assert_eq!(ids(book.get_by_price(&102).into_iter()), [2, 4]);
+}
+
+#[test]
+fn test_range_bounds_in_ascending_key_order() {
+    let mut book = MultiIndexBidMap::default();
+    for (id, price, quantity) in [(1, 100, 5), (2, 102, 1), (3, 99, 7), (4, 102, 3)] {
+        book.insert(Bid {
+            id,
+            price,
+            quantity,
+        });
+    }
+
+    // Ranges over a descending index are given in ascending order of the keys, and visited in descending order.
+    assert_eq!(ids(book.range_by_price(100..=102)), [2, 4, 1]);
+    assert_eq!(ids(book.range_by_price(101..)), [2, 4]);
+    assert_eq!(
+        ids(book.range_by_price((Bound::Unbounded, Bound::Excluded(101)))),
+        [1, 3]
+    );
+    // Inverted bounds are empty rather than panicking.
+    assert_eq!(
+        ids(book.range_by_price((Bound::Included(102), Bound::Included(100)))),
+        []
+    );
+    assert_eq!(
+        ids(book.range_by_price((Bound::Excluded(100), Bound::Excluded(100)))),
+        []
+    );
+
+    assert_eq!(book.rank_by_quantity(&7), 0);
+    assert_eq!(book.rank_by_quantity(&3), 2);
+    assert_eq!(book.nth_by_quantity(1).unwrap().id, 1);
+    assert_eq!(book.count_range_by_quantity(..6), 3);
+}
+
+#[test]
+fn test_modify_and_remove() {
+    let mut book = MultiIndexBidMap::default();
+    for (id, price, quantity) in [(1, 100, 5), (2, 102, 1), (3, 99, 7), (4, 102, 3)] {
+        book.insert(Bid {
+            id,
+            price,
+            quantity,
+        });
+    }
+
+    book.modify_by_id(&3, |b| b.price = 103);
+    assert_eq!(book.first_by_price().unwrap().id, 3);
+
+    let removed = book.remove_range_by_price(101..);
+    assert_eq!(removed.len(), 3);
+    assert_eq!(ids(book.iter_by_price()), [1]);
+
+    let mut book = MultiIndexBidMap::default();
+    for (id, price, quantity) in [(1, 100, 5), (2, 102, 1), (3, 99, 7), (4, 102, 3)] {
+        book.insert(Bid {
+            id,
+            price,
+            quantity,
+        });
+    }
+    let rest = book.split_off_by_price(&100);
+    assert_eq!(ids(book.iter_by_price()), [2, 4]);
+    assert_eq!(ids(rest.iter_by_price()), [1, 3]);
+
+    book.pop_first_by_quantity();
+    assert_eq!(ids(book.iter_by_quantity()), [2]);
+}
+
+#[derive(MultiIndexMap, Clone, Debug, PartialEq)]
+#[multi_index_derive(Debug, Clone)]
+struct Release {
+    #[multi_index(ordered_unique, compare = "cmp_version")]
+    version: String,
+    #[multi_index(ordered_non_unique, compare = "cmp_version", order = "desc")]
+    min_supported: String,
+}
+
+// Compares dotted version strings component by component, so that "1.10" follows "1.9".
+fn cmp_version(a: &String, b: &String) -> Ordering {
+    let parse = |v: &String| {
+        v.split('.')
+            .map(|c| c.parse::<u32>().unwrap_or(0))
+            .collect::<Vec<_>>()
+    };
+    parse(a).cmp(&parse(b))
+}
+
+#[test]
+fn test_user_defined_order() {
+    let mut map = MultiIndexReleaseMap::default();
+    for (version, min_supported) in [
+        ("1.10", "1.9"),
+        ("1.9", "1.2"),
+        ("2.0", "1.10"),
+        ("1.2", "1.2"),
+    ] {
+        map.insert(Release {
+            version: version.to_string(),
+            min_supported: min_supported.to_string(),
+        });
+    }
+
+    assert_eq!(
+        map.keys_by_version().cloned().collect::<Vec<_>>(),
+        ["1.2", "1.9", "1.10", "2.0"]
+    );
+    assert_eq!(
+        map.iter_by_min_supported()
+            .map(|r| r.version.as_str())
+            .collect::<Vec<_>>(),
+        ["2.0", "1.10", "1.9", "1.2"]
+    );
+
+    let from = "1.9".to_string();
+    assert_eq!(
+        map.range_by_version(&from..)
+            .map(|r| r.version.as_str())
+            .collect::<Vec<_>>(),
+        ["1.9", "1.10", "2.0"]
+    );
+
+    // Keys equal under the order are duplicates, even if they differ as strings.
+    let res = map.try_insert(Release {
+        version: "1.09".to_string(),
+        min_supported: "1.0".to_string(),
+    });
+    assert_eq!(res.unwrap_err().index, "version");
+
+    let cloned = map.clone();
+    assert_eq!(
+        cloned
+            .get_by_version(&"2.0".to_string())
+            .unwrap()
+            .min_supported,
+        "1.10"
+    );
+}
+
+#[test]
+fn test_try_modify_to_key_equal_under_order() {
+    let mut map = MultiIndexReleaseMap::default();
+    for (version, min_supported) in [("1.9", "1.2"), ("1.2", "1.2")] {
+        map.insert(Release {
+            version: version.to_string(),
+            min_supported: min_supported.to_string(),
+        });
+    }
+
+    // The element only collides with itself, so the modification is not a uniqueness violation.
+    let modified = map
+        .try_modify_by_version(&"1.9".to_string(), |r| r.version = "1.09".to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(modified.version, "1.09");
+    assert_eq!(
+        map.keys_by_version().cloned().collect::<Vec<_>>(),
+        ["1.2", "1.09"]
+    );
+
+    let modified = map
+        .try_modify_by_min_supported(&"1.2".to_string(), |r| {
+            r.version = r.version.replace('.', ".0")
+        })
+        .unwrap();
+    assert_eq!(modified.len(), 2);
+    assert_eq!(
+        map.keys_by_version().cloned().collect::<Vec<_>>(),
+        ["1.02", "1.009"]
+    );
+}
@@ -0,0 +1,204 @@
+use multi_index_map::MultiIndexMap;
+
+// Clippy flags `symbol` appearing in both composite indexes as a duplicated attribute.
+#[allow(clippy::duplicated_attributes)]
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+#[multi_index(hashed_unique, fields(account_id, symbol))]
+#[multi_index(ordered_non_unique, fields(symbol, price))]
+struct Position {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    account_id: u32,
+    symbol: String,
+    price: u64,
+    quantity: i64,
+}
+
+#[test]
+fn test_insert_and_get_by_composite() {
+    let mut map = MultiIndexPositionMap::default();
+    for (id, account_id, symbol, price, quantity) in [
+        (1, 10, "AAPL", 100, 5),
+        (2, 10, "MSFT", 200, 3),
+        (3, 11, "AAPL", 100, 7),
+    ] {
+        map.insert(Position {
+            id,
+            account_id,
+            symbol: symbol.to_string(),
+            price,
+            quantity,
+        });
+    }
+
+    let p = map
+        .get_by_account_id_symbol(&(10, "MSFT".to_string()))
+        .unwrap();
+    assert_eq!(p.id, 2);
+    assert!(map
+        .get_by_account_id_symbol(&(11, "MSFT".to_string()))
+        .is_none());
+
+    let res = map.try_insert(Position {
+        id: 4,
+        account_id: 11,
+        symbol: "AAPL".to_string(),
+        price: 300,
+        quantity: 1,
+    });
+    assert!(res.is_err());
+    assert_eq!(map.len(), 3);
+
+    let ps = map.get_by_symbol_price(&("AAPL".to_string(), 100));
+    assert_eq!(ps.len(), 2);
+}
+
+#[test]
+fn test_remove_by_composite() {
+    let mut map = MultiIndexPositionMap::default();
+    for (id, account_id, symbol, price, quantity) in [
+        (1, 10, "AAPL", 100, 5),
+        (2, 10, "MSFT", 200, 3),
+        (3, 11, "AAPL", 100, 7),
+    ] {
+        map.insert(Position {
+            id,
+            account_id,
+            symbol: symbol.to_string(),
+            price,
+            quantity,
+        });
+    }
+
+    let p = map
+        .remove_by_account_id_symbol(&(10, "AAPL".to_string()))
+        .unwrap();
+    assert_eq!(p.id, 1);
+    assert_eq!(map.len(), 2);
+    assert!(map.get_by_id(&1).is_none());
+    assert_eq!(map.get_by_symbol_price(&("AAPL".to_string(), 100)).len(), 1);
+
+    let ps = map.remove_by_symbol_price(&("AAPL".to_string(), 100));
+    assert_eq!(ps.len(), 1);
+    assert_eq!(ps[0].id, 3);
+    assert!(map
+        .get_by_account_id_symbol(&(11, "AAPL".to_string()))
+        .is_none());
+}
+
+#[test]
+fn test_modify_key_field_updates_composite() {
+    let mut map = MultiIndexPositionMap::default();
+    for (id, account_id, symbol, price, quantity) in [
+        (1, 10, "AAPL", 100, 5),
+        (2, 10, "MSFT", 200, 3),
+        (3, 11, "AAPL", 100, 7),
+    ] {
+        map.insert(Position {
+            id,
+            account_id,
+            symbol: symbol.to_string(),
+            price,
+            quantity,
+        });
+    }
+
+    map.modify_by_id(&1, |p| p.symbol = "GOOG".to_string());
+
+    assert!(map
+        .get_by_account_id_symbol(&(10, "AAPL".to_string()))
+        .is_none());
+    assert_eq!(
+        map.get_by_account_id_symbol(&(10, "GOOG".to_string()))
+            .unwrap()
+            .id,
+        1
+    );
+    assert_eq!(map.get_by_symbol_price(&("AAPL".to_string(), 100)).len(), 1);
+    assert_eq!(map.get_by_symbol_price(&("GOOG".to_string(), 100)).len(), 1);
+
+    let p = map
+        .modify_by_account_id_symbol(&(11, "AAPL".to_string()), |p| p.quantity = 0)
+        .unwrap();
+    assert_eq!(p.quantity, 0);
+}
+
+#[test]
+fn test_modify_violate_composite_uniqueness() {
+    let mut map = MultiIndexPositionMap::default();
+    for (id, account_id, symbol, price, quantity) in [
+        (1, 10, "AAPL", 100, 5),
+        (2, 10, "MSFT", 200, 3),
+        (3, 11, "AAPL", 100, 7),
+    ] {
+        map.insert(Position {
+            id,
+            account_id,
+            symbol: symbol.to_string(),
+            price,
+            quantity,
+        });
+    }
+
+    let res = std::panic::catch_unwind(move || {
+        map.modify_by_id(&3, |p| p.account_id = 10);
+    });
+
+    res.expect_err("Expected to violate uniqueness constraint");
+}
+
+#[test]
+fn test_iter_by_composite() {
+    let mut map = MultiIndexPositionMap::default();
+    for (id, account_id, symbol, price, quantity) in [
+        (1, 10, "AAPL", 100, 5),
+        (2, 10, "MSFT", 200, 3),
+        (3, 11, "AAPL", 100, 7),
+    ] {
+        map.insert(Position {
+            id,
+            account_id,
+            symbol: symbol.to_string(),
+            price,
+            quantity,
+        });
+    }
+
+    let keys = map
+        .iter_by_symbol_price()
+        .map(|p| (p.symbol.as_str(), p.price))
+        .collect::<Vec<_>>();
+    assert_eq!(keys, [("AAPL", 100), ("AAPL", 100), ("MSFT", 200)]);
+
+    assert_eq!(map.iter_by_account_id_symbol().count(), 3);
+}
+
+#[test]
+fn test_update_excludes_composite_key_fields() {
+    let mut map = MultiIndexPositionMap::default();
+    for (id, account_id, symbol, price, quantity) in [
+        (1, 10, "AAPL", 100, 5),
+        (2, 10, "MSFT", 200, 3),
+        (3, 11, "AAPL", 100, 7),
+    ] {
+        map.insert(Position {
+            id,
+            account_id,
+            symbol: symbol.to_string(),
+            price,
+            quantity,
+        });
+    }
+
+    // Only `quantity` is unindexed, as the other fields are keys of a composite index.
+    let p = map
+        .update_by_account_id_symbol(&(10, "AAPL".to_string()), |quantity| *quantity = 9)
+        .unwrap();
+    assert_eq!(p.quantity, 9);
+
+    for (quantity,) in map.iter_mut() {
+        *quantity += 1;
+    }
+    assert_eq!(map.get_by_id(&1).unwrap().quantity, 10);
+}
@@ -0,0 +1,209 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+#[multi_index(
+    hashed_non_unique,
+    key = "Self::normalized_name",
+    name = "norm_name",
+    ty = "String",
+    fields(name)
+)]
+#[multi_index(
+    ordered_unique,
+    key = "Self::tick",
+    name = "tick",
+    ty = "u64",
+    fields(price)
+)]
+struct Instrument {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    name: String,
+    price: u64,
+    volume: u64,
+}
+
+impl Instrument {
+    fn normalized_name(&self) -> String {
+        self.name.to_lowercase()
+    }
+
+    fn tick(&self) -> u64 {
+        self.price / 5
+    }
+}
+
+#[test]
+fn test_get_by_computed_key() {
+    let mut map = MultiIndexInstrumentMap::default();
+    for (id, name, price) in [(1, "AAPL", 100), (2, "aapl", 112), (3, "Msft", 201)] {
+        map.insert(Instrument {
+            id,
+            name: name.to_string(),
+            price,
+            volume: 0,
+        });
+    }
+
+    let mut ids = map
+        .get_by_norm_name(&"aapl".to_string())
+        .iter()
+        .map(|i| i.id)
+        .collect::<Vec<_>>();
+    ids.sort_unstable();
+    assert_eq!(ids, [1, 2]);
+    assert!(map.get_by_norm_name(&"AAPL".to_string()).is_empty());
+
+    assert_eq!(map.get_by_tick(&22).unwrap().id, 2);
+    assert_eq!(
+        map.iter_by_tick().map(|i| i.id).collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+}
+
+#[test]
+fn test_insert_violating_computed_key() {
+    let mut map = MultiIndexInstrumentMap::default();
+    for (id, name, price) in [(1, "AAPL", 100), (2, "aapl", 112), (3, "Msft", 201)] {
+        map.insert(Instrument {
+            id,
+            name: name.to_string(),
+            price,
+            volume: 0,
+        });
+    }
+
+    // A price of 104 falls in the same tick as 100.
+    let res = map.try_insert(Instrument {
+        id: 4,
+        name: "GOOG".to_string(),
+        price: 104,
+        volume: 0,
+    });
+    assert_eq!(res.unwrap_err().index, "tick");
+    assert_eq!(map.len(), 3);
+    assert!(map.get_by_norm_name(&"goog".to_string()).is_empty());
+}
+
+#[test]
+fn test_modify_recomputes_key() {
+    let mut map = MultiIndexInstrumentMap::default();
+    for (id, name, price) in [(1, "AAPL", 100), (2, "aapl", 112), (3, "Msft", 201)] {
+        map.insert(Instrument {
+            id,
+            name: name.to_string(),
+            price,
+            volume: 0,
+        });
+    }
+
+    map.modify_by_id(&3, |i| i.name = "AAPL".to_string());
+    assert_eq!(map.get_by_norm_name(&"aapl".to_string()).len(), 3);
+    assert!(map.get_by_norm_name(&"msft".to_string()).is_empty());
+
+    map.modify_by_tick(&20, |i| i.price = 300);
+    assert!(map.get_by_tick(&20).is_none());
+    assert_eq!(map.get_by_tick(&60).unwrap().id, 1);
+
+    let removed = map.remove_by_norm_name(&"aapl".to_string());
+    assert_eq!(removed.len(), 3);
+    assert!(map.is_empty());
+    assert!(map.get_by_tick(&60).is_none());
+}
+
+#[test]
+fn test_try_modify_restores_source_fields() {
+    let mut map = MultiIndexInstrumentMap::default();
+    for (id, name, price) in [(1, "AAPL", 100), (2, "aapl", 112), (3, "Msft", 201)] {
+        map.insert(Instrument {
+            id,
+            name: name.to_string(),
+            price,
+            volume: 0,
+        });
+    }
+
+    let res = map.try_modify_by_id(&1, |i| {
+        i.price = 113;
+        i.volume = 10;
+    });
+    assert_eq!(res.unwrap_err().index, "tick");
+
+    // The fields the key is computed from are restored along with the rest of the element.
+    let instrument = map.get_by_id(&1).unwrap();
+    assert_eq!(instrument.price, 100);
+    assert_eq!(instrument.volume, 0);
+    assert_eq!(map.get_by_tick(&20).unwrap().id, 1);
+}
+
+#[test]
+fn test_update_excludes_source_fields() {
+    let mut map = MultiIndexInstrumentMap::default();
+    for (id, name, price) in [(1, "AAPL", 100), (2, "aapl", 112), (3, "Msft", 201)] {
+        map.insert(Instrument {
+            id,
+            name: name.to_string(),
+            price,
+            volume: 0,
+        });
+    }
+
+    // Only `volume` is unindexed, as `name` and `price` are the fields the computed keys are computed from.
+    let instrument = map.update_by_id(&1, |volume| *volume = 7).unwrap();
+    assert_eq!(instrument.volume, 7);
+}
+
+// Unindexed fields need not implement Clone.
+#[derive(Debug, PartialEq)]
+struct Note(String);
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index(
+    ordered_non_unique,
+    key = "Trade::notional",
+    name = "notional",
+    ty = "u64",
+    fields(price, quantity)
+)]
+struct Trade {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    price: u64,
+    quantity: u64,
+    note: Note,
+}
+
+impl Trade {
+    fn notional(&self) -> u64 {
+        self.price * self.quantity
+    }
+}
+
+#[test]
+fn test_computed_key_from_several_fields() {
+    let mut map = MultiIndexTradeMap::default();
+    map.insert(Trade {
+        id: 1,
+        price: 10,
+        quantity: 3,
+        note: Note("a".to_string()),
+    });
+    map.insert(Trade {
+        id: 2,
+        price: 5,
+        quantity: 6,
+        note: Note("b".to_string()),
+    });
+
+    assert_eq!(map.get_by_notional(&30).len(), 2);
+
+    map.modify_by_id(&2, |t| t.quantity = 1);
+    assert_eq!(map.get_by_notional(&30).len(), 1);
+    assert_eq!(map.get_by_notional(&5)[0].id, 2);
+
+    let trade = map
+        .update_by_id(&1, |note| note.0 = "c".to_string())
+        .unwrap();
+    assert_eq!(trade.note, Note("c".to_string()));
+}
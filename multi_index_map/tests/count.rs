@@ -0,0 +1,81 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    field1: u32,
+    #[multi_index(ordered_unique)]
+    field2: String,
+    #[multi_index(hashed_non_unique)]
+    field3: String,
+    #[multi_index(ordered_non_unique)]
+    field4: u32,
+}
+
+#[test]
+fn test_count_by_non_unique() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..7 {
+        map.insert(TestElement {
+            field1: i,
+            field2: format!("name{i}"),
+            field3: format!("trader{}", i % 2),
+            field4: i / 3,
+        });
+    }
+
+    assert_eq!(map.count_by_field3("trader0"), 4);
+    assert_eq!(map.count_by_field3("trader1"), 3);
+    assert_eq!(map.count_by_field3("trader2"), 0);
+    assert_eq!(map.count_by_field4(&0), 3);
+    assert_eq!(map.count_by_field4(&2), 1);
+
+    map.remove_by_field1(&6);
+    assert_eq!(map.count_by_field4(&2), 0);
+    assert_eq!(map.count_by_field3("trader0"), 3);
+}
+
+#[test]
+fn test_contains() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..7 {
+        map.insert(TestElement {
+            field1: i,
+            field2: format!("name{i}"),
+            field3: format!("trader{}", i % 2),
+            field4: i / 3,
+        });
+    }
+
+    assert!(map.contains_field1(&0));
+    assert!(!map.contains_field1(&7));
+    assert!(map.contains_field2("name3"));
+    assert!(!map.contains_field2("name7"));
+    assert!(map.contains_field3("trader1"));
+    assert!(map.contains_field4(&2));
+
+    map.modify_by_field1(&6, |e| e.field4 = 1);
+    assert!(!map.contains_field4(&2));
+}
+
+#[test]
+fn test_distinct_count() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..7 {
+        map.insert(TestElement {
+            field1: i,
+            field2: format!("name{i}"),
+            field3: format!("trader{}", i % 2),
+            field4: i / 3,
+        });
+    }
+
+    assert_eq!(map.distinct_count_field1(), 7);
+    assert_eq!(map.distinct_count_field2(), 7);
+    assert_eq!(map.distinct_count_field3(), 2);
+    assert_eq!(map.distinct_count_field4(), 3);
+
+    map.clear();
+    assert_eq!(map.distinct_count_field3(), 0);
+}
@@ -24,3 +24,61 @@ fn should_compile() {
 
     let _s = serde_json::to_string(&map);
 }
+
+#[derive(MultiIndexMap, Deserialize, Serialize)]
+#[multi_index_derive(Deserialize, Serialize)]
+#[multi_index(sequenced)]
+struct TestSequencedElement {
+    #[multi_index(hashed_unique)]
+    field1: u64,
+}
+
+#[test]
+fn should_roundtrip_sequence() {
+    let mut map = MultiIndexTestSequencedElementMap::default();
+    map.insert(TestSequencedElement { field1: 2 });
+    map.push_front(TestSequencedElement { field1: 1 }).unwrap();
+
+    let s = serde_json::to_string(&map).unwrap();
+    let map: MultiIndexTestSequencedElementMap = serde_json::from_str(&s).unwrap();
+    let field1s = map.iter_sequenced().map(|e| e.field1).collect::<Vec<_>>();
+    assert_eq!(field1s, [1, 2]);
+}
+
+#[derive(MultiIndexMap, Deserialize, Serialize)]
+#[multi_index_derive(Deserialize, Serialize)]
+struct TestEquivalenceElement {
+    #[multi_index(hashed_unique, eq = "ascii_case_insensitive")]
+    field1: String,
+}
+
+#[test]
+fn should_roundtrip_equivalence() {
+    let mut map = MultiIndexTestEquivalenceElementMap::default();
+    map.insert(TestEquivalenceElement {
+        field1: "Hello".to_string(),
+    });
+
+    let s = serde_json::to_string(&map).unwrap();
+    let map: MultiIndexTestEquivalenceElementMap = serde_json::from_str(&s).unwrap();
+    assert_eq!(map.get_by_field1("HELLO").unwrap().field1, "Hello");
+}
+
+#[derive(MultiIndexMap, Deserialize, Serialize)]
+#[multi_index_derive(Deserialize, Serialize)]
+struct TestOrderElement {
+    #[multi_index(ordered_unique, order = "desc")]
+    field1: u64,
+}
+
+#[test]
+fn should_roundtrip_order() {
+    let mut map = MultiIndexTestOrderElementMap::default();
+    map.insert(TestOrderElement { field1: 1 });
+    map.insert(TestOrderElement { field1: 2 });
+
+    let s = serde_json::to_string(&map).unwrap();
+    let map: MultiIndexTestOrderElementMap = serde_json::from_str(&s).unwrap();
+    let field1s = map.iter_by_field1().map(|e| e.field1).collect::<Vec<_>>();
+    assert_eq!(field1s, [2, 1]);
+}
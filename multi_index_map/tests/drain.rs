@@ -0,0 +1,99 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+#[multi_index_handles(generational)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    field1: u32,
+    #[multi_index(ordered_non_unique)]
+    field2: u32,
+    field3: String,
+}
+
+#[test]
+fn test_into_iter() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..5 {
+        map.insert(TestElement {
+            field1: i,
+            field2: i % 2,
+            field3: i.to_string(),
+        });
+    }
+
+    let mut elems = map.into_iter().collect::<Vec<_>>();
+    elems.sort_by_key(|e| e.field1);
+    assert_eq!(elems.len(), 5);
+    assert_eq!(elems[3].field3, "3");
+}
+
+#[test]
+fn test_into_iter_across_threads() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..5 {
+        map.insert(TestElement {
+            field1: i,
+            field2: i % 2,
+            field3: i.to_string(),
+        });
+    }
+
+    let handle =
+        std::thread::spawn(move || map.into_iter().map(|e| e.field3).collect::<Vec<String>>());
+    assert_eq!(handle.join().unwrap().len(), 5);
+}
+
+#[test]
+fn test_drain() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..5 {
+        map.insert(TestElement {
+            field1: i,
+            field2: i % 2,
+            field3: i.to_string(),
+        });
+    }
+    let key = map.insert(TestElement {
+        field1: 10,
+        field2: 0,
+        field3: "10".to_string(),
+    });
+
+    let mut elems = map.drain().collect::<Vec<_>>();
+    elems.sort_by_key(|e| e.field1);
+    assert_eq!(elems.len(), 6);
+    assert_eq!(elems[5].field1, 10);
+
+    assert!(map.is_empty());
+    assert!(map.get_by_field1(&10).is_none());
+    assert!(map.get_by_field2(&0).is_empty());
+    assert_eq!(map.iter_by_field2().count(), 0);
+    assert!(map.get(key).is_none());
+
+    // The map remains usable after draining, including elements with previously used keys.
+    let new_key = map.insert(TestElement {
+        field1: 10,
+        field2: 0,
+        field3: "new".to_string(),
+    });
+    assert!(map.get(key).is_none());
+    assert_eq!(map.get(new_key).unwrap().field3, "new");
+    assert_eq!(map.get_by_field2(&0).len(), 1);
+}
+
+#[test]
+fn test_drain_dropped_early() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..5 {
+        map.insert(TestElement {
+            field1: i,
+            field2: i % 2,
+            field3: i.to_string(),
+        });
+    }
+
+    assert!(map.drain().next().is_some());
+    assert!(map.is_empty());
+    assert!(map.get_by_field1(&0).is_none());
+}
@@ -0,0 +1,183 @@
+use multi_index_map::{MultiIndexMap, VacantEntryError};
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+#[multi_index(hashed_unique, fields(field2, field3))]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    field1: u32,
+    #[multi_index(ordered_unique)]
+    field2: u32,
+    field3: String,
+    field4: u64,
+}
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+#[multi_index(sequenced)]
+#[multi_index_handles(generational)]
+struct SequencedElement {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    #[multi_index(ordered_unique)]
+    name: String,
+}
+
+#[test]
+fn test_entry_vacant_insert() {
+    let mut map = MultiIndexTestElementMap::default();
+
+    match map.entry_by_field1(1) {
+        MultiIndexTestElementMapField1Entry::Occupied(_) => panic!("Entry should be vacant"),
+        MultiIndexTestElementMapField1Entry::Vacant(entry) => {
+            assert_eq!(*entry.key(), 1);
+            let elem = entry
+                .insert(TestElement {
+                    field1: 1,
+                    field2: 10,
+                    field3: "a".to_string(),
+                    field4: 0,
+                })
+                .unwrap();
+            assert_eq!(elem.field2, 10);
+        }
+    }
+
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get_by_field2(&10).unwrap().field1, 1);
+}
+
+#[test]
+fn test_entry_occupied() {
+    let mut map = MultiIndexTestElementMap::default();
+    let key = map.insert(TestElement {
+        field1: 1,
+        field2: 10,
+        field3: "a".to_string(),
+        field4: 0,
+    });
+
+    let MultiIndexTestElementMapField2Entry::Occupied(mut entry) = map.entry_by_field2(10) else {
+        panic!("Entry should be occupied");
+    };
+    assert_eq!(entry.key(), key);
+    assert_eq!(entry.get().field1, 1);
+    entry.update(|field4| *field4 = 5);
+    let elem = entry.modify(|e| e.field2 = 20);
+    assert_eq!(elem.field4, 5);
+
+    assert!(map.get_by_field2(&10).is_none());
+    assert_eq!(map.get_by_field2(&20).unwrap().field1, 1);
+
+    let MultiIndexTestElementMapField2Field3Entry::Occupied(entry) =
+        map.entry_by_field2_field3((20, "a".to_string()))
+    else {
+        panic!("Entry should be occupied");
+    };
+    assert_eq!(entry.remove().field1, 1);
+    assert!(map.is_empty());
+    assert!(map.get_by_field1(&1).is_none());
+}
+
+#[test]
+fn test_entry_vacant_insert_collides_in_other_index() {
+    let mut map = MultiIndexTestElementMap::default();
+    let key = map.insert(TestElement {
+        field1: 1,
+        field2: 10,
+        field3: "a".to_string(),
+        field4: 0,
+    });
+
+    let MultiIndexTestElementMapField1Entry::Vacant(entry) = map.entry_by_field1(2) else {
+        panic!("Entry should be vacant");
+    };
+    let Err(VacantEntryError::Uniqueness(err)) = entry.insert(TestElement {
+        field1: 2,
+        field2: 10,
+        field3: "b".to_string(),
+        field4: 0,
+    }) else {
+        panic!("Expected to violate uniqueness constraint");
+    };
+    assert_eq!(err.index, "field2");
+    assert_eq!(err.existing, key);
+    assert_eq!(err.elem.field1, 2);
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_entry_vacant_insert_wrong_key() {
+    let mut map = MultiIndexTestElementMap::default();
+
+    let MultiIndexTestElementMapField1Entry::Vacant(entry) = map.entry_by_field1(1) else {
+        panic!("Entry should be vacant");
+    };
+    let err = entry
+        .insert(TestElement {
+            field1: 2,
+            field2: 10,
+            field3: "a".to_string(),
+            field4: 0,
+        })
+        .unwrap_err();
+    assert!(matches!(err, VacantEntryError::KeyMismatch(_)));
+    assert_eq!(err.into_elem().field1, 2);
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_entry_vacant_insert_sequenced() {
+    let mut map = MultiIndexSequencedElementMap::default();
+    let first = map.insert(SequencedElement {
+        id: 1,
+        name: "a".to_string(),
+    });
+    map.remove(first);
+
+    let MultiIndexSequencedElementMapIdEntry::Vacant(entry) = map.entry_by_id(2) else {
+        panic!("Entry should be vacant");
+    };
+    assert_eq!(entry.key(), &2);
+    entry
+        .insert(SequencedElement {
+            id: 2,
+            name: "b".to_string(),
+        })
+        .unwrap();
+
+    // The element reuses the slot of the removed one, so the stale key handle must not reach it.
+    assert!(map.get(first).is_none());
+    assert_eq!(map.get_by_name(&"b".to_string()).unwrap().id, 2);
+    assert_eq!(map.iter_sequenced().map(|e| e.id).collect::<Vec<_>>(), [2]);
+
+    let MultiIndexSequencedElementMapNameEntry::Vacant(entry) = map.entry_by_name("c".to_string())
+    else {
+        panic!("Entry should be vacant");
+    };
+    entry
+        .insert(SequencedElement {
+            id: 3,
+            name: "c".to_string(),
+        })
+        .unwrap();
+    assert_eq!(
+        map.iter_sequenced().map(|e| e.id).collect::<Vec<_>>(),
+        [2, 3]
+    );
+
+    // A collision in another index leaves the key of the entry vacant.
+    let MultiIndexSequencedElementMapNameEntry::Vacant(entry) = map.entry_by_name("d".to_string())
+    else {
+        panic!("Entry should be vacant");
+    };
+    let Err(VacantEntryError::Uniqueness(err)) = entry.insert(SequencedElement {
+        id: 3,
+        name: "d".to_string(),
+    }) else {
+        panic!("Insert should collide in the id index");
+    };
+    assert_eq!(err.index, "id");
+    assert!(map.get_by_name(&"d".to_string()).is_none());
+    assert_eq!(map.len(), 2);
+}
@@ -0,0 +1,237 @@
+use std::hash::{Hash, Hasher};
+
+use multi_index_map::{KeyEquivalence, MultiIndexMap};
+
+#[derive(MultiIndexMap, Clone, Debug, PartialEq)]
+#[multi_index_derive(Debug, Clone)]
+struct User {
+    #[multi_index(hashed_unique, eq = "ascii_case_insensitive")]
+    username: String,
+    #[multi_index(hashed_non_unique, eq = "ascii_case_insensitive")]
+    team: String,
+    #[multi_index(hashed_unique, eq = "LastDigits")]
+    phone: u64,
+    logins: u32,
+}
+
+// Phone numbers are equivalent when their last four digits are equal.
+struct LastDigits;
+
+impl KeyEquivalence<u64> for LastDigits {
+    fn hash<H: Hasher>(key: &u64, state: &mut H) {
+        (key % 10_000).hash(state);
+    }
+
+    fn eq(a: &u64, b: &u64) -> bool {
+        a % 10_000 == b % 10_000
+    }
+}
+
+#[test]
+fn test_get_by_borrowed_key_ignoring_case() {
+    let mut map = MultiIndexUserMap::default();
+    for (username, team, phone) in [
+        ("Alice", "Red", 5551234),
+        ("bob", "red", 5555678),
+        ("Carol", "Blue", 5559999),
+    ] {
+        map.insert(User {
+            username: username.to_string(),
+            team: team.to_string(),
+            phone,
+            logins: 0,
+        });
+    }
+
+    assert_eq!(map.get_by_username("alice").unwrap().phone, 5551234);
+    assert_eq!(map.get_by_username("BOB").unwrap().team, "red");
+    assert_eq!(map.get_by_username(&"CaRoL".to_string()).unwrap().logins, 0);
+    assert!(map.get_by_username("dave").is_none());
+
+    assert_eq!(map.get_by_team("RED").len(), 2);
+    assert_eq!(map.count_by_team("blue"), 1);
+    assert!(map.contains_team("Blue"));
+    assert_eq!(map.distinct_count_team(), 2);
+
+    // Keys are returned as they were inserted.
+    let mut keys = map.keys_by_username().cloned().collect::<Vec<_>>();
+    keys.sort_unstable();
+    assert_eq!(keys, ["Alice", "Carol", "bob"]);
+    let mut groups = map
+        .groups_by_team()
+        .map(|(team, group)| (team.to_lowercase(), group.count()))
+        .collect::<Vec<_>>();
+    groups.sort_unstable();
+    assert_eq!(groups, [("blue".to_string(), 1), ("red".to_string(), 2)]);
+}
+
+#[test]
+fn test_uniqueness_ignoring_case() {
+    let mut map = MultiIndexUserMap::default();
+    for (username, team, phone) in [
+        ("Alice", "Red", 5551234),
+        ("bob", "red", 5555678),
+        ("Carol", "Blue", 5559999),
+    ] {
+        map.insert(User {
+            username: username.to_string(),
+            team: team.to_string(),
+            phone,
+            logins: 0,
+        });
+    }
+
+    let res = map.try_insert(User {
+        username: "ALICE".to_string(),
+        team: "Green".to_string(),
+        phone: 5550000,
+        logins: 0,
+    });
+    assert_eq!(res.unwrap_err().index, "username");
+
+    // The custom equivalence type only compares the last four digits.
+    let res = map.try_insert(User {
+        username: "dave".to_string(),
+        team: "Green".to_string(),
+        phone: 4441234,
+        logins: 0,
+    });
+    assert_eq!(res.unwrap_err().index, "phone");
+    assert_eq!(map.get_by_phone(&9991234).unwrap().username, "Alice");
+    assert_eq!(map.len(), 3);
+}
+
+#[test]
+fn test_modify_and_remove() {
+    let mut map = MultiIndexUserMap::default();
+    for (username, team, phone) in [
+        ("Alice", "Red", 5551234),
+        ("bob", "red", 5555678),
+        ("Carol", "Blue", 5559999),
+    ] {
+        map.insert(User {
+            username: username.to_string(),
+            team: team.to_string(),
+            phone,
+            logins: 0,
+        });
+    }
+
+    // Changing only the case keeps the element reachable, and updates the stored key.
+    map.modify_by_username(&"alice".to_string(), |u| u.username = "ALICE".to_string());
+    assert_eq!(map.get_by_username("Alice").unwrap().username, "ALICE");
+    assert!(map.keys_by_username().any(|k| k == "ALICE"));
+
+    map.modify_by_team(&"red".to_string(), |u| u.team = "Blue".to_string());
+    assert_eq!(map.get_by_team("blue").len(), 3);
+    assert!(map.get_by_team("red").is_empty());
+
+    let res = map.try_modify_by_username(&"bob".to_string(), |u| u.username = "carol".to_string());
+    assert_eq!(res.unwrap_err().index, "username");
+    assert_eq!(map.get_by_username("BOB").unwrap().username, "bob");
+
+    assert_eq!(
+        map.update_by_username("CAROL", |logins| *logins += 1)
+            .unwrap()
+            .logins,
+        1
+    );
+
+    let removed = map.remove_by_username(&"Carol".to_string()).unwrap();
+    assert_eq!(removed.phone, 5559999);
+    assert_eq!(map.remove_by_team(&"BLUE".to_string()).len(), 2);
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_try_modify_to_equivalent_key() {
+    let mut map = MultiIndexUserMap::default();
+    for (username, team, phone) in [("alice", "Red", 5551234), ("bob", "red", 5555678)] {
+        map.insert(User {
+            username: username.to_string(),
+            team: team.to_string(),
+            phone,
+            logins: 0,
+        });
+    }
+
+    // The element only collides with itself, so the modification is not a uniqueness violation.
+    let modified = map
+        .try_modify_by_username(&"alice".to_string(), |u| u.username = "ALICE".to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(modified.username, "ALICE");
+    assert!(map.keys_by_username().any(|k| k == "ALICE"));
+
+    let modified = map
+        .try_modify_by_phone(&5555678, |u| u.phone = 4445678)
+        .unwrap()
+        .unwrap();
+    assert_eq!(modified.username, "bob");
+    assert_eq!(map.get_by_phone(&5678).unwrap().phone, 4445678);
+
+    let modified = map
+        .try_modify_by_team(&"RED".to_string(), |u| {
+            u.username = u.username.to_lowercase()
+        })
+        .unwrap();
+    assert_eq!(modified.len(), 2);
+    assert_eq!(map.get_by_username("Alice").unwrap().username, "alice");
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn test_entry_and_upsert_ignoring_case() {
+    let mut map = MultiIndexUserMap::default();
+    for (username, team, phone) in [
+        ("Alice", "Red", 5551234),
+        ("bob", "red", 5555678),
+        ("Carol", "Blue", 5559999),
+    ] {
+        map.insert(User {
+            username: username.to_string(),
+            team: team.to_string(),
+            phone,
+            logins: 0,
+        });
+    }
+
+    assert!(matches!(
+        map.entry_by_username("aLiCe".to_string()),
+        MultiIndexUserMapUsernameEntry::Occupied(_)
+    ));
+
+    let replaced = map
+        .upsert_by_username(User {
+            username: "BOB".to_string(),
+            team: "Green".to_string(),
+            phone: 5555678,
+            logins: 3,
+        })
+        .unwrap();
+    assert_eq!(replaced.unwrap().username, "bob");
+    assert_eq!(map.get_by_username("bob").unwrap().logins, 3);
+
+    let cloned = map.clone();
+    assert_eq!(cloned.get_by_team("green").len(), 1);
+}
+
+#[test]
+fn test_hash_agrees_with_borrowed_form() {
+    fn hash_of(key: &str) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        <multi_index_map::AsciiCaseInsensitive as KeyEquivalence<str>>::hash(key, &mut hasher);
+        hasher.finish()
+    }
+
+    assert_eq!(hash_of("Hello"), hash_of("hELLO"));
+    let owned = {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        <multi_index_map::AsciiCaseInsensitive as KeyEquivalence<String>>::hash(
+            &"HELLO".to_string(),
+            &mut hasher,
+        );
+        hasher.finish()
+    };
+    assert_eq!(owned, hash_of("hello"));
+}
@@ -0,0 +1,299 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Status {
+    Open,
+    Filled,
+}
+
+#[derive(MultiIndexMap, Clone, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+struct Order {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    #[multi_index(ordered_non_unique, filter = "Self::is_open", filter_fields(status))]
+    price: u32,
+    #[multi_index(hashed_unique, filter = "Self::is_open", filter_fields(status))]
+    client_ref: u32,
+    status: Status,
+    quantity: u32,
+}
+
+impl Order {
+    fn is_open(&self) -> bool {
+        self.status == Status::Open
+    }
+}
+
+fn prices(map: &MultiIndexOrderMap) -> Vec<(u32, u32)> {
+    map.iter_by_price().map(|o| (o.price, o.order_id)).collect()
+}
+
+#[test]
+fn test_only_matching_elements_are_indexed() {
+    let mut map = MultiIndexOrderMap::default();
+    for (order_id, price, client_ref, status) in [
+        (1, 100, 10, Status::Open),
+        (2, 100, 10, Status::Filled),
+        (3, 90, 11, Status::Open),
+        (4, 110, 11, Status::Filled),
+    ] {
+        map.insert(Order {
+            order_id,
+            price,
+            client_ref,
+            status,
+            quantity: 1,
+        });
+    }
+
+    assert_eq!(map.len(), 4);
+    assert_eq!(prices(&map), [(90, 3), (100, 1)]);
+    assert!(map.get_by_price(&110).is_empty());
+    assert_eq!(map.get_by_client_ref(&10).unwrap().order_id, 1);
+    // Every element is still in the indexes without a filter.
+    assert_eq!(map.get_by_order_id(&4).unwrap().price, 110);
+}
+
+#[test]
+fn test_uniqueness_only_among_matching_elements() {
+    let mut map = MultiIndexOrderMap::default();
+    for (order_id, price, client_ref, status) in [
+        (1, 100, 10, Status::Open),
+        (2, 100, 10, Status::Filled),
+        (3, 90, 11, Status::Open),
+        (4, 110, 11, Status::Filled),
+    ] {
+        map.insert(Order {
+            order_id,
+            price,
+            client_ref,
+            status,
+            quantity: 1,
+        });
+    }
+
+    // Filled orders never collide on a partial index.
+    map.try_insert(Order {
+        order_id: 5,
+        price: 120,
+        client_ref: 10,
+        status: Status::Filled,
+        quantity: 1,
+    })
+    .unwrap();
+
+    let res = map.try_insert(Order {
+        order_id: 6,
+        price: 120,
+        client_ref: 10,
+        status: Status::Open,
+        quantity: 1,
+    });
+    assert_eq!(res.unwrap_err().index, "client_ref");
+    assert_eq!(map.len(), 5);
+}
+
+#[test]
+fn test_modify_adds_and_drops_elements() {
+    let mut map = MultiIndexOrderMap::default();
+    for (order_id, price, client_ref, status) in [
+        (1, 100, 10, Status::Open),
+        (2, 100, 10, Status::Filled),
+        (3, 90, 11, Status::Open),
+        (4, 110, 11, Status::Filled),
+    ] {
+        map.insert(Order {
+            order_id,
+            price,
+            client_ref,
+            status,
+            quantity: 1,
+        });
+    }
+
+    map.modify_by_order_id(&1, |o| o.status = Status::Filled);
+    assert_eq!(prices(&map), [(90, 3)]);
+    assert!(map.get_by_client_ref(&10).is_none());
+
+    // Changing the key of an element outside the partial index leaves the lookup table alone.
+    map.modify_by_order_id(&2, |o| o.price = 95);
+    assert_eq!(prices(&map), [(90, 3)]);
+
+    map.modify_by_price(&90, |o| {
+        o.price = 80;
+        o.client_ref = 12;
+    });
+    assert_eq!(prices(&map), [(80, 3)]);
+
+    map.modify_by_order_id(&4, |o| o.status = Status::Open);
+    assert_eq!(prices(&map), [(80, 3), (110, 4)]);
+    assert_eq!(map.get_by_client_ref(&12).unwrap().order_id, 3);
+    assert_eq!(map.get_by_client_ref(&11).unwrap().order_id, 4);
+}
+
+#[test]
+fn test_remove_unindexed_element_with_same_key() {
+    let mut map = MultiIndexOrderMap::default();
+    for (order_id, price, client_ref, status) in [
+        (1, 100, 10, Status::Open),
+        (2, 100, 10, Status::Filled),
+        (3, 90, 11, Status::Open),
+        (4, 110, 11, Status::Filled),
+    ] {
+        map.insert(Order {
+            order_id,
+            price,
+            client_ref,
+            status,
+            quantity: 1,
+        });
+    }
+
+    // Order 2 has the same price and client_ref as order 1, but is not in the partial indexes.
+    map.remove_by_order_id(&2);
+    assert_eq!(prices(&map), [(90, 3), (100, 1)]);
+    assert_eq!(map.get_by_client_ref(&10).unwrap().order_id, 1);
+
+    let removed = map.remove_by_price(&100);
+    assert_eq!(removed.len(), 1);
+    assert!(map.get_by_client_ref(&10).is_none());
+}
+
+#[test]
+fn test_try_modify_restores_filter_fields() {
+    let mut map = MultiIndexOrderMap::default();
+    for (order_id, price, client_ref, status) in [
+        (1, 100, 10, Status::Open),
+        (2, 100, 10, Status::Filled),
+        (3, 90, 11, Status::Open),
+        (4, 110, 11, Status::Filled),
+    ] {
+        map.insert(Order {
+            order_id,
+            price,
+            client_ref,
+            status,
+            quantity: 1,
+        });
+    }
+
+    // Reopening order 4 would collide with order 3 in client_ref.
+    let res = map.try_modify_by_order_id(&4, |o| {
+        o.status = Status::Open;
+        o.quantity = 5;
+    });
+    assert_eq!(res.unwrap_err().index, "client_ref");
+
+    let order = map.get_by_order_id(&4).unwrap();
+    assert_eq!(order.status, Status::Filled);
+    assert_eq!(order.quantity, 1);
+    assert_eq!(prices(&map), [(90, 3), (100, 1)]);
+    assert_eq!(map.get_by_client_ref(&11).unwrap().order_id, 3);
+}
+
+#[test]
+fn test_upsert_by_partial_index() {
+    let mut map = MultiIndexOrderMap::default();
+    for (order_id, price, client_ref, status) in [
+        (1, 100, 10, Status::Open),
+        (2, 100, 10, Status::Filled),
+        (3, 90, 11, Status::Open),
+        (4, 110, 11, Status::Filled),
+    ] {
+        map.insert(Order {
+            order_id,
+            price,
+            client_ref,
+            status,
+            quantity: 1,
+        });
+    }
+
+    let replaced = map
+        .upsert_by_client_ref(Order {
+            order_id: 7,
+            price: 105,
+            client_ref: 10,
+            status: Status::Open,
+            quantity: 1,
+        })
+        .unwrap();
+    assert_eq!(replaced.unwrap().order_id, 1);
+
+    // A filled element replaces nothing, as it would not be in the lookup table.
+    let replaced = map
+        .upsert_by_client_ref(Order {
+            order_id: 8,
+            price: 105,
+            client_ref: 10,
+            status: Status::Filled,
+            quantity: 1,
+        })
+        .unwrap();
+    assert!(replaced.is_none());
+    assert_eq!(map.get_by_client_ref(&10).unwrap().order_id, 7);
+    assert_eq!(map.len(), 5);
+}
+
+#[test]
+fn test_update_excludes_filter_fields() {
+    let mut map = MultiIndexOrderMap::default();
+    for (order_id, price, client_ref, status) in [
+        (1, 100, 10, Status::Open),
+        (2, 100, 10, Status::Filled),
+        (3, 90, 11, Status::Open),
+        (4, 110, 11, Status::Filled),
+    ] {
+        map.insert(Order {
+            order_id,
+            price,
+            client_ref,
+            status,
+            quantity: 1,
+        });
+    }
+
+    // Only `quantity` is unindexed, as `status` is read by the filters.
+    let order = map
+        .update_by_order_id(&1, |quantity| *quantity = 3)
+        .unwrap();
+    assert_eq!(order.quantity, 3);
+}
+
+#[test]
+fn test_try_modify_non_unique_rolls_back() {
+    let mut map = MultiIndexOrderMap::default();
+    for (order_id, price, client_ref, status) in [
+        (1, 100, 10, Status::Open),
+        (2, 100, 10, Status::Filled),
+        (3, 90, 11, Status::Open),
+        (4, 110, 11, Status::Filled),
+    ] {
+        map.insert(Order {
+            order_id,
+            price,
+            client_ref,
+            status,
+            quantity: 1,
+        });
+    }
+    map.insert(Order {
+        order_id: 5,
+        price: 100,
+        client_ref: 20,
+        status: Status::Open,
+        quantity: 1,
+    });
+
+    // The first order moved to client_ref 30 makes the second collide, so both are rolled back.
+    let res = map.try_modify_by_price(&100, |o| {
+        o.client_ref = 30;
+    });
+    assert_eq!(res.unwrap_err().index, "client_ref");
+
+    assert_eq!(prices(&map), [(90, 3), (100, 1), (100, 5)]);
+    assert_eq!(map.get_by_client_ref(&10).unwrap().order_id, 1);
+    assert_eq!(map.get_by_client_ref(&20).unwrap().order_id, 5);
+    assert!(map.get_by_client_ref(&30).is_none());
+}
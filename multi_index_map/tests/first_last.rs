@@ -0,0 +1,82 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+struct Order {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    #[multi_index(ordered_unique)]
+    timestamp: u64,
+    #[multi_index(ordered_non_unique)]
+    price: u32,
+}
+
+#[test]
+fn test_first_last_by_unique() {
+    let mut map = MultiIndexOrderMap::default();
+    for (order_id, timestamp, price) in [(1, 30, 100), (2, 10, 90), (3, 20, 100), (4, 40, 90)] {
+        map.insert(Order {
+            order_id,
+            timestamp,
+            price,
+        });
+    }
+
+    assert_eq!(map.first_by_timestamp().unwrap().order_id, 2);
+    assert_eq!(map.last_by_timestamp().unwrap().order_id, 4);
+}
+
+#[test]
+fn test_first_last_by_non_unique() {
+    let mut map = MultiIndexOrderMap::default();
+    for (order_id, timestamp, price) in [(1, 30, 100), (2, 10, 90), (3, 20, 100), (4, 40, 90)] {
+        map.insert(Order {
+            order_id,
+            timestamp,
+            price,
+        });
+    }
+
+    // Among elements with the same key, the first inserted is first, and the last inserted is last.
+    assert_eq!(map.first_by_price().unwrap().order_id, 2);
+    assert_eq!(map.last_by_price().unwrap().order_id, 3);
+}
+
+#[test]
+fn test_pop_first_last() {
+    let mut map = MultiIndexOrderMap::default();
+    for (order_id, timestamp, price) in [(1, 30, 100), (2, 10, 90), (3, 20, 100), (4, 40, 90)] {
+        map.insert(Order {
+            order_id,
+            timestamp,
+            price,
+        });
+    }
+
+    let order = map.pop_first_by_timestamp().unwrap();
+    assert_eq!(order.order_id, 2);
+    assert!(map.get_by_order_id(&2).is_none());
+    assert_eq!(map.get_by_price(&90).len(), 1);
+
+    let order = map.pop_last_by_price().unwrap();
+    assert_eq!(order.order_id, 3);
+    assert!(map.get_by_timestamp(&20).is_none());
+    assert_eq!(map.len(), 2);
+
+    let ids = std::iter::from_fn(|| map.pop_first_by_price())
+        .map(|o| o.order_id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids, [4, 1]);
+    assert!(map.is_empty());
+    assert_eq!(map.iter_by_order_id().count(), 0);
+}
+
+#[test]
+fn test_first_last_empty() {
+    let mut map = MultiIndexOrderMap::default();
+
+    assert!(map.first_by_timestamp().is_none());
+    assert!(map.last_by_price().is_none());
+    assert!(map.pop_first_by_price().is_none());
+    assert!(map.pop_last_by_timestamp().is_none());
+}
@@ -0,0 +1,96 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Debug, Clone)]
+#[multi_index_derive(Debug, Clone)]
+#[multi_index_handles(generational)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    field1: u32,
+    #[multi_index(hashed_non_unique)]
+    field2: u32,
+}
+
+#[test]
+fn test_stale_key_after_slot_reuse() {
+    let mut map = MultiIndexTestElementMap::default();
+    let key1 = map.insert(TestElement {
+        field1: 1,
+        field2: 0,
+    });
+    map.remove_by_field1(&1).unwrap();
+
+    // The new element reuses the vacant slot of the first one.
+    let key2 = map.insert(TestElement {
+        field1: 2,
+        field2: 0,
+    });
+    assert_ne!(key1, key2);
+
+    assert!(map.get(key1).is_none());
+    assert!(map.modify(key1, |e| e.field1 = 3).is_none());
+    assert!(map.update(key1, || {}).is_none());
+    assert!(map.remove(key1).is_none());
+
+    assert_eq!(map.get(key2).unwrap().field1, 2);
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_stale_key_after_non_unique_remove() {
+    let mut map = MultiIndexTestElementMap::default();
+    let key1 = map.insert(TestElement {
+        field1: 1,
+        field2: 0,
+    });
+    let key2 = map.insert(TestElement {
+        field1: 2,
+        field2: 0,
+    });
+    map.remove_by_field2(&0);
+
+    map.insert(TestElement {
+        field1: 3,
+        field2: 0,
+    });
+    map.insert(TestElement {
+        field1: 4,
+        field2: 0,
+    });
+    assert!(map.get(key1).is_none());
+    assert!(map.get(key2).is_none());
+}
+
+#[test]
+fn test_stale_key_after_clear_and_shrink() {
+    let mut map = MultiIndexTestElementMap::default();
+    let key1 = map.insert(TestElement {
+        field1: 1,
+        field2: 0,
+    });
+    map.clear();
+    map.shrink_to_fit();
+
+    map.insert(TestElement {
+        field1: 2,
+        field2: 0,
+    });
+    assert!(map.get(key1).is_none());
+    assert_eq!(map.get_by_field1(&2).unwrap().field2, 0);
+}
+
+#[test]
+fn test_key_survives_clone() {
+    let mut map = MultiIndexTestElementMap::default();
+    let key = map.insert(TestElement {
+        field1: 1,
+        field2: 0,
+    });
+    map.remove(key);
+    let key = map.insert(TestElement {
+        field1: 2,
+        field2: 0,
+    });
+
+    let cloned = map.clone();
+    assert_eq!(cloned.get(key).unwrap().field1, 2);
+}
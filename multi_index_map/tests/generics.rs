@@ -37,3 +37,32 @@ fn should_compile() {
     };
     map.insert(elem1);
 }
+
+fn cmp_len(a: &String, b: &String) -> std::cmp::Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+#[derive(MultiIndexMap, Clone, Debug)]
+#[multi_index_derive(Clone, Debug)]
+struct TestComparedElement<T: Clone> {
+    #[multi_index(ordered_unique, compare = "cmp_len")]
+    name: String,
+    #[allow(dead_code)]
+    payload: T,
+}
+
+#[test]
+fn should_compile_with_compare() {
+    let mut map = MultiIndexTestComparedElementMap::default();
+    for name in ["ccc", "a", "bb"] {
+        map.insert(TestComparedElement {
+            name: name.to_string(),
+            payload: vec![0u8],
+        });
+    }
+
+    assert_eq!(
+        map.keys_by_name().cloned().collect::<Vec<_>>(),
+        ["a", "bb", "ccc"]
+    );
+}
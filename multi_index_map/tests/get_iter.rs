@@ -0,0 +1,79 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    field1: u32,
+    #[multi_index(hashed_non_unique)]
+    field2: String,
+    #[multi_index(ordered_non_unique)]
+    field3: u32,
+}
+
+#[test]
+fn test_get_iter_by_hashed_non_unique() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..6 {
+        map.insert(TestElement {
+            field1: i,
+            field2: format!("name{}", i % 2),
+            field3: i / 3,
+        });
+    }
+
+    let iter = map.get_iter_by_field2("name0");
+    assert_eq!(iter.len(), 3);
+    let field1s = iter.map(|e| e.field1).collect::<Vec<_>>();
+    assert_eq!(field1s, [0, 2, 4]);
+
+    let field1s = map
+        .get_iter_by_field2("name1")
+        .rev()
+        .map(|e| e.field1)
+        .collect::<Vec<_>>();
+    assert_eq!(field1s, [5, 3, 1]);
+}
+
+#[test]
+fn test_get_iter_by_ordered_non_unique() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..6 {
+        map.insert(TestElement {
+            field1: i,
+            field2: format!("name{}", i % 2),
+            field3: i / 3,
+        });
+    }
+
+    let mut iter = map.get_iter_by_field3(&1);
+    assert_eq!(iter.next().unwrap().field1, 3);
+    assert_eq!(iter.next_back().unwrap().field1, 5);
+    assert_eq!(iter.len(), 1);
+    assert_eq!(iter.next().unwrap().field1, 4);
+    assert!(iter.next().is_none());
+    assert!(iter.next_back().is_none());
+
+    assert_eq!(
+        map.get_iter_by_field3(&0).collect::<Vec<_>>(),
+        map.get_by_field3(&0)
+    );
+}
+
+#[test]
+fn test_get_iter_by_missing_key() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..6 {
+        map.insert(TestElement {
+            field1: i,
+            field2: format!("name{}", i % 2),
+            field3: i / 3,
+        });
+    }
+
+    let mut iter = map.get_iter_by_field3(&10);
+    assert_eq!(iter.len(), 0);
+    assert!(iter.next().is_none());
+    assert!(iter.next_back().is_none());
+    assert_eq!(map.get_iter_by_field2("missing").count(), 0);
+}
@@ -0,0 +1,88 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+struct TestElement {
+    #[multi_index(ordered_unique)]
+    field1: u32,
+    #[multi_index(hashed_non_unique)]
+    field2: String,
+    #[multi_index(ordered_non_unique)]
+    field3: u32,
+}
+
+#[test]
+fn test_keys_by() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..7 {
+        map.insert(TestElement {
+            field1: 6 - i,
+            field2: format!("trader{}", i % 2),
+            field3: i / 3,
+        });
+    }
+
+    let keys = map.keys_by_field1().copied().collect::<Vec<_>>();
+    assert_eq!(keys, [0, 1, 2, 3, 4, 5, 6]);
+
+    let keys = map.keys_by_field3().rev().copied().collect::<Vec<_>>();
+    assert_eq!(keys, [2, 1, 0]);
+
+    let mut keys = map.keys_by_field2().cloned().collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(keys, ["trader0", "trader1"]);
+}
+
+#[test]
+fn test_groups_by_non_unique() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..7 {
+        map.insert(TestElement {
+            field1: 6 - i,
+            field2: format!("trader{}", i % 2),
+            field3: i / 3,
+        });
+    }
+
+    let groups = map
+        .groups_by_field3()
+        .map(|(key, elems)| (*key, elems.map(|e| e.field1).collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        groups,
+        [(0, vec![6, 5, 4]), (1, vec![3, 2, 1]), (2, vec![0])]
+    );
+
+    let (key, elems) = map.groups_by_field3().next_back().unwrap();
+    assert_eq!(*key, 2);
+    assert_eq!(elems.len(), 1);
+
+    let groups = map.groups_by_field2();
+    assert_eq!(groups.len(), 2);
+    for (key, elems) in groups {
+        assert!(elems.map(|e| &e.field2).all(|name| name == key));
+    }
+}
+
+#[test]
+fn test_groups_by_unique() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..7 {
+        map.insert(TestElement {
+            field1: 6 - i,
+            field2: format!("trader{}", i % 2),
+            field3: i / 3,
+        });
+    }
+    map.remove_by_field1(&3);
+
+    let groups = map
+        .groups_by_field1()
+        .map(|(key, mut elems)| {
+            let elem = elems.next().unwrap();
+            assert!(elems.next().is_none());
+            (*key, elem.field3)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(groups, [(0, 2), (1, 1), (2, 1), (4, 0), (5, 0), (6, 0)]);
+}
@@ -0,0 +1,91 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    field1: u32,
+    #[multi_index(ordered_non_unique)]
+    field2: u32,
+    field3: String,
+}
+
+#[test]
+fn test_insert_and_get_by_key() {
+    let mut map = MultiIndexTestElementMap::default();
+    let key1 = map.insert(TestElement {
+        field1: 1,
+        field2: 10,
+        field3: "one".to_string(),
+    });
+    let key2 = map
+        .try_insert(TestElement {
+            field1: 2,
+            field2: 10,
+            field3: "two".to_string(),
+        })
+        .unwrap();
+    assert_ne!(key1, key2);
+
+    assert_eq!(map.get(key1).unwrap().field3, "one");
+    assert_eq!(map.get(key2).unwrap().field3, "two");
+}
+
+#[test]
+fn test_remove_by_key() {
+    let mut map = MultiIndexTestElementMap::default();
+    let key1 = map.insert(TestElement {
+        field1: 1,
+        field2: 10,
+        field3: "one".to_string(),
+    });
+    map.insert(TestElement {
+        field1: 2,
+        field2: 10,
+        field3: "two".to_string(),
+    });
+
+    let elem = map.remove(key1).unwrap();
+    assert_eq!(elem.field1, 1);
+    assert_eq!(map.len(), 1);
+    assert!(map.get(key1).is_none());
+    assert!(map.get_by_field1(&1).is_none());
+    assert_eq!(map.get_by_field2(&10).len(), 1);
+
+    assert!(map.remove(key1).is_none());
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_modify_and_update_by_key() {
+    let mut map = MultiIndexTestElementMap::default();
+    let key = map.insert(TestElement {
+        field1: 1,
+        field2: 10,
+        field3: "one".to_string(),
+    });
+
+    let elem = map
+        .modify(key, |e| {
+            e.field1 = 5;
+            e.field2 = 50;
+        })
+        .unwrap();
+    assert_eq!(elem.field1, 5);
+
+    // The key remains valid after changing indexed fields.
+    assert_eq!(map.get(key).unwrap().field2, 50);
+    assert!(map.get_by_field1(&1).is_none());
+    assert_eq!(map.get_by_field1(&5).unwrap().field3, "one");
+    assert!(map.get_by_field2(&10).is_empty());
+    assert_eq!(map.get_by_field2(&50).len(), 1);
+
+    let elem = map
+        .update(key, |field3| *field3 = "updated".to_string())
+        .unwrap();
+    assert_eq!(elem.field3, "updated");
+
+    map.remove(key);
+    assert!(map.modify(key, |e| e.field1 = 6).is_none());
+    assert!(map.update(key, |_| {}).is_none());
+}
@@ -0,0 +1,262 @@
+use std::collections::BTreeSet;
+
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+struct Article {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    #[multi_index(hashed_multi)]
+    tags: Vec<String>,
+    #[multi_index(ordered_multi)]
+    years: BTreeSet<u16>,
+    views: u64,
+}
+
+fn ids(articles: Vec<&Article>) -> Vec<u32> {
+    let mut ids = articles.iter().map(|a| a.id).collect::<Vec<_>>();
+    ids.sort_unstable();
+    ids
+}
+
+#[test]
+fn test_indexed_under_every_item() {
+    let mut map = MultiIndexArticleMap::default();
+    for (id, tags, years) in [
+        (1, &["rust", "db"][..], &[2020, 2021][..]),
+        (2, &["rust"][..], &[2021][..]),
+        (3, &["go", "db"][..], &[2019][..]),
+        (4, &[][..], &[][..]),
+    ] {
+        map.insert(Article {
+            id,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            years: years.iter().copied().collect(),
+            views: 0,
+        });
+    }
+
+    assert_eq!(ids(map.get_by_tags(&"rust".to_string())), [1, 2]);
+    assert_eq!(ids(map.get_by_tags(&"db".to_string())), [1, 3]);
+    assert!(map.get_by_tags(&"java".to_string()).is_empty());
+    assert_eq!(map.count_by_tags(&"go".to_string()), 1);
+    assert_eq!(map.distinct_count_tags(), 3);
+
+    assert_eq!(ids(map.get_by_years(&2021)), [1, 2]);
+    // Elements are visited once for every item of their collection.
+    assert_eq!(
+        map.iter_by_years().map(|a| a.id).collect::<Vec<_>>(),
+        [3, 1, 1, 2]
+    );
+}
+
+#[test]
+fn test_modify_diffs_collections() {
+    let mut map = MultiIndexArticleMap::default();
+    for (id, tags, years) in [
+        (1, &["rust", "db"][..], &[2020, 2021][..]),
+        (2, &["rust"][..], &[2021][..]),
+        (3, &["go", "db"][..], &[2019][..]),
+        (4, &[][..], &[][..]),
+    ] {
+        map.insert(Article {
+            id,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            years: years.iter().copied().collect(),
+            views: 0,
+        });
+    }
+
+    map.modify_by_id(&1, |a| {
+        a.tags.retain(|t| t != "db");
+        a.tags.push("web".to_string());
+    });
+    assert_eq!(ids(map.get_by_tags(&"rust".to_string())), [1, 2]);
+    assert_eq!(ids(map.get_by_tags(&"db".to_string())), [3]);
+    assert_eq!(ids(map.get_by_tags(&"web".to_string())), [1]);
+
+    map.modify_by_tags(&"rust".to_string(), |a| a.tags.clear());
+    assert!(!map.contains_tags(&"rust".to_string()));
+    assert_eq!(map.distinct_count_tags(), 2);
+
+    map.modify_by_id(&4, |a| {
+        a.years.insert(2019);
+    });
+    assert_eq!(ids(map.get_by_years(&2019)), [3, 4]);
+}
+
+#[test]
+fn test_duplicate_items() {
+    let mut map = MultiIndexArticleMap::default();
+    for (id, tags, years) in [
+        (1, &["rust", "db"][..], &[2020, 2021][..]),
+        (2, &["rust"][..], &[2021][..]),
+        (3, &["go", "db"][..], &[2019][..]),
+        (4, &[][..], &[][..]),
+    ] {
+        map.insert(Article {
+            id,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            years: years.iter().copied().collect(),
+            views: 0,
+        });
+    }
+    map.insert(Article {
+        id: 5,
+        tags: vec!["rust".to_string(), "rust".to_string()],
+        years: BTreeSet::new(),
+        views: 0,
+    });
+    assert_eq!(ids(map.get_by_tags(&"rust".to_string())), [1, 2, 5]);
+
+    // Dropping one copy of a duplicate item keeps the element under it.
+    map.modify_by_id(&5, |a| {
+        a.tags.pop();
+    });
+    assert_eq!(ids(map.get_by_tags(&"rust".to_string())), [1, 2, 5]);
+
+    map.modify_by_id(&5, |a| a.tags.push("rust".to_string()));
+    map.remove_by_id(&5);
+    assert_eq!(ids(map.get_by_tags(&"rust".to_string())), [1, 2]);
+}
+
+#[test]
+fn test_remove() {
+    let mut map = MultiIndexArticleMap::default();
+    for (id, tags, years) in [
+        (1, &["rust", "db"][..], &[2020, 2021][..]),
+        (2, &["rust"][..], &[2021][..]),
+        (3, &["go", "db"][..], &[2019][..]),
+        (4, &[][..], &[][..]),
+    ] {
+        map.insert(Article {
+            id,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            years: years.iter().copied().collect(),
+            views: 0,
+        });
+    }
+
+    let removed = map.remove_by_tags(&"db".to_string());
+    assert_eq!(removed.len(), 2);
+    assert_eq!(ids(map.get_by_tags(&"rust".to_string())), [2]);
+    assert!(map.get_by_tags(&"go".to_string()).is_empty());
+    assert!(map.get_by_years(&2020).is_empty());
+    assert_eq!(map.len(), 2);
+
+    map.remove_by_id(&4);
+    map.remove_by_id(&2);
+    assert_eq!(map.distinct_count_tags(), 0);
+    assert_eq!(map.distinct_count_years(), 0);
+}
+
+#[test]
+fn test_remove_range_visits_each_element_once() {
+    let mut map = MultiIndexArticleMap::default();
+    for (id, years) in [(1, &[2020, 2021][..]), (2, &[2021][..]), (3, &[2019][..])] {
+        map.insert(Article {
+            id,
+            tags: Vec::new(),
+            years: years.iter().copied().collect(),
+            views: 0,
+        });
+    }
+
+    let removed = map.remove_range_by_years(2020..2030);
+    assert_eq!(removed.iter().map(|a| a.id).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(map.len(), 1);
+    assert!(map.get_by_years(&2021).is_empty());
+    assert_eq!(ids(map.get_by_years(&2019)), [3]);
+}
+
+#[test]
+fn test_modify_range_visits_each_element_once() {
+    let mut map = MultiIndexArticleMap::default();
+    for (id, years) in [(1, &[2020, 2021][..]), (2, &[2021][..]), (3, &[2019][..])] {
+        map.insert(Article {
+            id,
+            tags: Vec::new(),
+            years: years.iter().copied().collect(),
+            views: 0,
+        });
+    }
+
+    let modified = map.modify_range_by_years(2020..2030, |a| a.views += 1);
+    assert_eq!(modified.iter().map(|a| a.id).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(map.get_by_id(&1).unwrap().views, 1);
+    assert_eq!(map.get_by_id(&2).unwrap().views, 1);
+    assert_eq!(map.get_by_id(&3).unwrap().views, 0);
+}
+
+#[test]
+fn test_split_off_visits_each_element_once() {
+    let mut map = MultiIndexArticleMap::default();
+    for (id, years) in [(1, &[2020, 2021][..]), (2, &[2021][..]), (3, &[2019][..])] {
+        map.insert(Article {
+            id,
+            tags: Vec::new(),
+            years: years.iter().copied().collect(),
+            views: 0,
+        });
+    }
+
+    let other = map.split_off_by_years(&2020);
+    assert_eq!(other.len(), 2);
+    assert_eq!(ids(other.get_by_years(&2021)), [1, 2]);
+    assert_eq!(ids(other.get_by_years(&2020)), [1]);
+    assert_eq!(map.len(), 1);
+    assert_eq!(ids(map.get_by_years(&2019)), [3]);
+}
+
+#[derive(MultiIndexMap, Clone, Debug, PartialEq)]
+struct Label {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    #[multi_index(ordered_unique)]
+    slug: String,
+    #[multi_index(hashed_multi, filter = "Self::is_public", filter_fields(public))]
+    tags: Vec<u32>,
+    public: bool,
+}
+
+impl Label {
+    fn is_public(&self) -> bool {
+        self.public
+    }
+}
+
+#[test]
+fn test_filtered_and_try_modify() {
+    let mut map = MultiIndexLabelMap::default();
+    for (id, slug, tags, public) in [(1, "a", vec![1, 2], true), (2, "b", vec![2], false)] {
+        map.insert(Label {
+            id,
+            slug: slug.to_string(),
+            tags,
+            public,
+        });
+    }
+    assert_eq!(map.count_by_tags(&2), 1);
+
+    map.modify_by_id(&2, |l| {
+        l.public = true;
+        l.tags.push(3);
+    });
+    assert_eq!(map.count_by_tags(&2), 2);
+    assert_eq!(map.count_by_tags(&3), 1);
+
+    map.modify_by_id(&1, |l| l.public = false);
+    assert_eq!(map.count_by_tags(&1), 0);
+    assert_eq!(map.count_by_tags(&2), 1);
+
+    // Taking the slug of another label rolls back the change to the tags.
+    let res = map.try_modify_by_tags(&3, |l| {
+        l.tags = vec![4];
+        l.slug = "a".to_string();
+    });
+    assert_eq!(res.unwrap_err().index, "slug");
+    assert_eq!(map.get_by_id(&2).unwrap().tags, [2, 3]);
+    assert_eq!(map.count_by_tags(&3), 1);
+    assert_eq!(map.count_by_tags(&4), 0);
+}
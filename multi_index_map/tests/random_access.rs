@@ -0,0 +1,108 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Debug, Clone, PartialEq)]
+#[multi_index_derive(Debug, Clone)]
+#[multi_index(random_access)]
+struct Row {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    #[multi_index(ordered_non_unique)]
+    group: u32,
+}
+
+fn row(id: u32) -> Row {
+    Row { id, group: id % 2 }
+}
+
+fn ids(map: &MultiIndexRowMap) -> Vec<u32> {
+    map.iter_random_access().map(|r| r.id).collect()
+}
+
+#[test]
+fn test_nth() {
+    let mut map = MultiIndexRowMap::default();
+    for i in 0..5 {
+        map.insert(row(i));
+    }
+    map.remove_by_id(&1);
+
+    // Positions are dense, so removing an element shifts every later element down.
+    assert_eq!(map.nth(0).unwrap().id, 0);
+    assert_eq!(map.nth(1).unwrap().id, 2);
+    assert_eq!(map.nth(3).unwrap().id, 4);
+    assert!(map.nth(4).is_none());
+
+    // The reused slot is appended at the end.
+    map.insert(row(5));
+    assert_eq!(ids(&map), [0, 2, 3, 4, 5]);
+    assert_eq!(map.iter_random_access().nth(2).unwrap().id, 3);
+    assert_eq!(map.iter_random_access().next_back().unwrap().id, 5);
+}
+
+#[test]
+fn test_insert_at_and_position_of() {
+    let mut map = MultiIndexRowMap::default();
+    let key0 = map.insert(row(0));
+    let key1 = map.insert(row(1));
+    let key2 = map.insert_at(1, row(2)).unwrap();
+    let key3 = map.insert_at(0, row(3)).unwrap();
+    map.insert_at(4, row(4)).unwrap();
+    assert_eq!(ids(&map), [3, 0, 2, 1, 4]);
+
+    assert_eq!(map.position_of(key3), Some(0));
+    assert_eq!(map.position_of(key0), Some(1));
+    assert_eq!(map.position_of(key2), Some(2));
+    assert_eq!(map.position_of(key1), Some(3));
+
+    let err = map.insert_at(0, row(2)).unwrap_err();
+    assert_eq!(err.existing, key2);
+    assert_eq!(ids(&map), [3, 0, 2, 1, 4]);
+
+    map.remove(key0);
+    assert_eq!(map.position_of(key0), None);
+    assert_eq!(map.position_of(key1), Some(2));
+}
+
+#[test]
+#[should_panic]
+fn test_insert_at_out_of_bounds() {
+    let mut map = MultiIndexRowMap::default();
+    map.insert(row(0));
+    let _ = map.insert_at(2, row(1));
+}
+
+#[test]
+fn test_remove_nth() {
+    let mut map = MultiIndexRowMap::default();
+    for i in 0..5 {
+        map.insert(row(i));
+    }
+
+    assert_eq!(map.remove_nth(1).unwrap().id, 1);
+    assert!(map.get_by_id(&1).is_none());
+    assert_eq!(map.get_by_group(&1).len(), 1);
+    assert_eq!(ids(&map), [0, 2, 3, 4]);
+    assert!(map.remove_nth(4).is_none());
+
+    map.retain(|r| r.group == 0);
+    assert_eq!(ids(&map), [0, 2, 4]);
+    let cloned = map.clone();
+    map.clear();
+    assert!(map.nth(0).is_none());
+    assert_eq!(ids(&cloned), [0, 2, 4]);
+}
+
+#[test]
+fn test_split_off_keeps_positions() {
+    let mut map = MultiIndexRowMap::default();
+    map.insert(row(3));
+    map.insert(row(1));
+    map.insert_at(0, row(5)).unwrap();
+    map.insert_at(1, row(2)).unwrap();
+    map.insert(row(7));
+    assert_eq!(ids(&map), [5, 2, 3, 1, 7]);
+
+    let other = map.split_off_by_group(&1);
+    assert_eq!(ids(&other), [5, 3, 1, 7]);
+    assert_eq!(ids(&map), [2]);
+}
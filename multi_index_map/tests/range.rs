@@ -0,0 +1,142 @@
+use std::ops::Bound;
+
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Debug)]
+#[multi_index_derive(Debug)]
+struct TestElement {
+    #[multi_index(ordered_unique)]
+    timestamp: u64,
+    #[multi_index(ordered_non_unique)]
+    bucket: u32,
+    #[multi_index(ordered_unique)]
+    name: String,
+}
+
+#[test]
+fn test_range_by_unique() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..10u64 {
+        map.insert(TestElement {
+            timestamp: 100 + i,
+            bucket: (i / 3) as u32,
+            name: format!("name{i}"),
+        });
+    }
+
+    let ts = map
+        .range_by_timestamp(102..105)
+        .map(|e| e.timestamp)
+        .collect::<Vec<_>>();
+    assert_eq!(ts, [102, 103, 104]);
+
+    let ts = map
+        .range_by_timestamp(107..)
+        .rev()
+        .map(|e| e.timestamp)
+        .collect::<Vec<_>>();
+    assert_eq!(ts, [109, 108, 107]);
+
+    assert_eq!(map.range_by_timestamp(..=100).count(), 1);
+    assert_eq!(map.range_by_timestamp(200..).count(), 0);
+}
+
+#[test]
+fn test_range_by_non_unique() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..10u64 {
+        map.insert(TestElement {
+            timestamp: 100 + i,
+            bucket: (i / 3) as u32,
+            name: format!("name{i}"),
+        });
+    }
+
+    let ts = map
+        .range_by_bucket(1..=2)
+        .map(|e| e.timestamp)
+        .collect::<Vec<_>>();
+    assert_eq!(ts, [103, 104, 105, 106, 107, 108]);
+
+    let ts = map
+        .range_by_bucket(1..=2)
+        .rev()
+        .map(|e| e.timestamp)
+        .collect::<Vec<_>>();
+    assert_eq!(ts, [108, 107, 106, 105, 104, 103]);
+}
+
+#[test]
+fn test_range_by_non_unique_both_ends() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..10u64 {
+        map.insert(TestElement {
+            timestamp: 100 + i,
+            bucket: (i / 3) as u32,
+            name: format!("name{i}"),
+        });
+    }
+
+    // Both ends of the iterator must meet without yielding any element twice,
+    //   including when they meet inside a single key's set of elements.
+    let mut it = map.range_by_bucket(0..2);
+    assert_eq!(it.next().unwrap().timestamp, 100);
+    assert_eq!(it.next_back().unwrap().timestamp, 105);
+    assert_eq!(it.next_back().unwrap().timestamp, 104);
+    assert_eq!(it.next_back().unwrap().timestamp, 103);
+    assert_eq!(it.next_back().unwrap().timestamp, 102);
+    assert_eq!(it.next().unwrap().timestamp, 101);
+    assert!(it.next().is_none());
+    assert!(it.next_back().is_none());
+}
+
+#[test]
+fn test_range_by_borrowed_key() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..10u64 {
+        map.insert(TestElement {
+            timestamp: 100 + i,
+            bucket: (i / 3) as u32,
+            name: format!("name{i}"),
+        });
+    }
+
+    let names = map
+        .range_by_name::<str, _>((Bound::Included("name3"), Bound::Excluded("name6")))
+        .map(|e| e.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["name3", "name4", "name5"]);
+}
+
+#[test]
+fn test_inverted_range_is_empty() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..10u64 {
+        map.insert(TestElement {
+            timestamp: 100 + i,
+            bucket: (i / 3) as u32,
+            name: format!("name{i}"),
+        });
+    }
+
+    // The start bound is after the end bound.
+    let inverted = (Bound::Included(107), Bound::Excluded(103));
+    assert_eq!(map.range_by_timestamp(inverted).count(), 0);
+    assert_eq!(
+        map.range_by_bucket((Bound::Included(2), Bound::Included(1)))
+            .count(),
+        0
+    );
+    // The bounds are equal, but both excluded.
+    assert_eq!(
+        map.range_by_bucket((Bound::Excluded(1), Bound::Excluded(1)))
+            .count(),
+        0
+    );
+
+    assert!(map.remove_range_by_timestamp(inverted).is_empty());
+    assert!(map
+        .modify_range_by_bucket((Bound::Excluded(2), Bound::Included(0)), |e| e.bucket += 1)
+        .is_empty());
+    assert_eq!(map.len(), 10);
+}
@@ -0,0 +1,106 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+struct Session {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    #[multi_index(ordered_unique)]
+    last_seen: u64,
+    #[multi_index(ordered_non_unique)]
+    priority: u32,
+}
+
+#[test]
+fn test_remove_range_by_unique() {
+    let mut map = MultiIndexSessionMap::default();
+    for i in 0..8 {
+        map.insert(Session {
+            id: i,
+            last_seen: 100 + u64::from(i),
+            priority: i % 3,
+        });
+    }
+
+    let expired = map.remove_range_by_last_seen(..104);
+    let ids = expired.iter().map(|s| s.id).collect::<Vec<_>>();
+    assert_eq!(ids, [0, 1, 2, 3]);
+    assert_eq!(map.len(), 4);
+    assert!(map.get_by_id(&0).is_none());
+    assert_eq!(map.get_by_priority(&0).len(), 1);
+    assert_eq!(map.first_by_last_seen().unwrap().id, 4);
+
+    assert!(map.remove_range_by_last_seen(200..).is_empty());
+}
+
+#[test]
+fn test_remove_range_by_non_unique() {
+    let mut map = MultiIndexSessionMap::default();
+    for i in 0..8 {
+        map.insert(Session {
+            id: i,
+            last_seen: 100 + u64::from(i),
+            priority: i % 3,
+        });
+    }
+
+    let removed = map.remove_range_by_priority(1..);
+    assert_eq!(removed.len(), 5);
+    assert!(removed.iter().all(|s| s.priority >= 1));
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.iter_by_last_seen().count(), 3);
+}
+
+#[test]
+fn test_modify_range_by() {
+    let mut map = MultiIndexSessionMap::default();
+    for i in 0..8 {
+        map.insert(Session {
+            id: i,
+            last_seen: 100 + u64::from(i),
+            priority: i % 3,
+        });
+    }
+
+    let modified = map.modify_range_by_priority(0..=1, |s| s.priority += 10);
+    assert_eq!(modified.len(), 6);
+    assert!(map.get_by_priority(&0).is_empty());
+    assert!(map.get_by_priority(&1).is_empty());
+    assert_eq!(map.get_by_priority(&10).len(), 3);
+    assert_eq!(map.get_by_priority(&11).len(), 3);
+}
+
+#[test]
+fn test_modify_range_by_moves_elements_within_range() {
+    let mut map = MultiIndexSessionMap::default();
+    for i in 0..8 {
+        map.insert(Session {
+            id: i,
+            last_seen: 100 + u64::from(i),
+            priority: i % 3,
+        });
+    }
+
+    // Each element is moved further along the range being walked, but is only visited once.
+    let mut visited = 0;
+    map.modify_range_by_last_seen(100..110, |s| {
+        visited += 1;
+        s.last_seen += 1000;
+    });
+    assert_eq!(visited, 8);
+    let last_seen = map
+        .iter_by_last_seen()
+        .map(|s| s.last_seen)
+        .collect::<Vec<_>>();
+    assert_eq!(last_seen, [1100, 1101, 1102, 1103, 1104, 1105, 1106, 1107]);
+
+    // Elements moved into an earlier part of the range are not visited again either.
+    let mut visited = 0;
+    map.modify_range_by_priority(1..=2, |s| {
+        visited += 1;
+        s.priority -= 1;
+    });
+    assert_eq!(visited, 5);
+    assert_eq!(map.get_by_priority(&0).len(), 6);
+    assert_eq!(map.get_by_priority(&1).len(), 2);
+}
@@ -0,0 +1,224 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+struct Player {
+    #[multi_index(hashed_unique)]
+    name: String,
+    #[multi_index(ordered_ranked)]
+    score: u32,
+    #[multi_index(ordered_unique)]
+    rating: u32,
+}
+
+#[test]
+fn test_rank_by() {
+    let mut map = MultiIndexPlayerMap::default();
+    for (name, score, rating) in [
+        ("alice", 30, 1),
+        ("bob", 10, 2),
+        ("carol", 20, 3),
+        ("dave", 30, 4),
+        ("erin", 50, 5),
+    ] {
+        map.insert(Player {
+            name: name.to_string(),
+            score,
+            rating,
+        });
+    }
+
+    assert_eq!(map.rank_by_score(&10), 0);
+    assert_eq!(map.rank_by_score(&20), 1);
+    // Elements with equal keys share a rank.
+    assert_eq!(map.rank_by_score(&30), 2);
+    assert_eq!(map.rank_by_score(&50), 4);
+    // Keys which are not present are ranked by where they would be inserted.
+    assert_eq!(map.rank_by_score(&0), 0);
+    assert_eq!(map.rank_by_score(&40), 4);
+    assert_eq!(map.rank_by_score(&60), 5);
+}
+
+#[test]
+fn test_nth_by() {
+    let mut map = MultiIndexPlayerMap::default();
+    for (name, score, rating) in [
+        ("alice", 30, 1),
+        ("bob", 10, 2),
+        ("carol", 20, 3),
+        ("dave", 30, 4),
+        ("erin", 50, 5),
+    ] {
+        map.insert(Player {
+            name: name.to_string(),
+            score,
+            rating,
+        });
+    }
+
+    let names = (0..map.len())
+        .map(|n| map.nth_by_score(n).unwrap().name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["bob", "carol", "alice", "dave", "erin"]);
+    assert!(map.nth_by_score(5).is_none());
+}
+
+#[test]
+fn test_count_range_by() {
+    let mut map = MultiIndexPlayerMap::default();
+    for (name, score, rating) in [
+        ("alice", 30, 1),
+        ("bob", 10, 2),
+        ("carol", 20, 3),
+        ("dave", 30, 4),
+        ("erin", 50, 5),
+    ] {
+        map.insert(Player {
+            name: name.to_string(),
+            score,
+            rating,
+        });
+    }
+
+    assert_eq!(map.count_range_by_score(..), 5);
+    assert_eq!(map.count_range_by_score(20..=30), 3);
+    assert_eq!(map.count_range_by_score(20..30), 1);
+    assert_eq!(map.count_range_by_score(30..), 3);
+    assert_eq!(map.count_range_by_score(..30), 2);
+    assert_eq!(map.count_range_by_score(31..50), 0);
+}
+
+#[test]
+fn test_ranks_follow_modify_and_remove() {
+    let mut map = MultiIndexPlayerMap::default();
+    for (name, score, rating) in [
+        ("alice", 30, 1),
+        ("bob", 10, 2),
+        ("carol", 20, 3),
+        ("dave", 30, 4),
+        ("erin", 50, 5),
+    ] {
+        map.insert(Player {
+            name: name.to_string(),
+            score,
+            rating,
+        });
+    }
+
+    map.modify_by_name(&"bob".to_string(), |p| p.score = 40);
+    assert_eq!(map.rank_by_score(&40), 3);
+    assert_eq!(map.nth_by_score(0).unwrap().name, "carol");
+    assert_eq!(map.nth_by_score(3).unwrap().name, "bob");
+
+    map.remove_by_name(&"alice".to_string());
+    assert_eq!(map.rank_by_score(&40), 2);
+    assert_eq!(map.count_range_by_score(30..=30), 1);
+
+    let removed = map.remove_by_score(&40);
+    assert_eq!(removed.len(), 1);
+    assert_eq!(map.count_range_by_score(..), 3);
+    assert_eq!(map.nth_by_score(2).unwrap().name, "erin");
+
+    map.clear();
+    assert_eq!(map.count_range_by_score(..), 0);
+    assert!(map.nth_by_score(0).is_none());
+}
+
+#[test]
+fn test_ranks_roll_back_with_try_modify() {
+    let mut map = MultiIndexPlayerMap::default();
+    for (name, score, rating) in [
+        ("alice", 30, 1),
+        ("bob", 10, 2),
+        ("carol", 20, 3),
+        ("dave", 30, 4),
+        ("erin", 50, 5),
+    ] {
+        map.insert(Player {
+            name: name.to_string(),
+            score,
+            rating,
+        });
+    }
+
+    // Taking dave's rating violates the rating index, so the score change must be rolled back too.
+    let res = map.try_modify_by_name(&"alice".to_string(), |p| {
+        p.score = 0;
+        p.rating = 4;
+    });
+    assert!(res.is_err());
+    assert_eq!(map.rank_by_score(&30), 2);
+    assert_eq!(map.count_range_by_score(..10), 0);
+    assert_eq!(map.nth_by_score(0).unwrap().name, "bob");
+}
+
+#[test]
+fn test_ranks_match_sorted_scores() {
+    let mut map = MultiIndexPlayerMap::default();
+    // Simple linear congruential generator, so the sequence of operations is reproducible.
+    let mut seed = 12345_u64;
+    let mut next = move || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) as u32
+    };
+
+    for rating in 0..500 {
+        let name = format!("player{}", next() % 200);
+        let score = next() % 50;
+        match next() % 3 {
+            0 => {
+                map.remove_by_name(&name);
+            }
+            1 => {
+                map.modify_by_name(&name, |p| p.score = score);
+            }
+            _ => {
+                let _ = map.try_insert(Player {
+                    name,
+                    score,
+                    rating,
+                });
+            }
+        }
+    }
+
+    let mut scores = map.iter().map(|(_, p)| p.score).collect::<Vec<_>>();
+    scores.sort_unstable();
+    for (n, &score) in scores.iter().enumerate() {
+        assert_eq!(map.nth_by_score(n).unwrap().score, score);
+        assert_eq!(
+            map.rank_by_score(&score),
+            scores.iter().filter(|&&s| s < score).count()
+        );
+    }
+    assert_eq!(
+        map.count_range_by_score(10..20),
+        scores.iter().filter(|&&s| (10..20).contains(&s)).count()
+    );
+}
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index(ordered_ranked, fields(wins, losses))]
+struct Team {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    wins: u32,
+    losses: u32,
+}
+
+#[test]
+fn test_composite_ranked() {
+    let mut map = MultiIndexTeamMap::default();
+    for (id, wins, losses) in [(1, 3, 1), (2, 1, 3), (3, 3, 0), (4, 2, 2)] {
+        map.insert(Team { id, wins, losses });
+    }
+
+    assert_eq!(map.rank_by_wins_losses(&(3, 0)), 2);
+    assert_eq!(map.nth_by_wins_losses(3).unwrap().id, 1);
+    assert_eq!(map.count_range_by_wins_losses((2, 0)..(3, 1)), 2);
+
+    map.modify_by_id(&2, |t| t.wins = 4);
+    assert_eq!(map.nth_by_wins_losses(3).unwrap().id, 2);
+}
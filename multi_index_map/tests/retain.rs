@@ -0,0 +1,92 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    field1: u32,
+    #[multi_index(ordered_non_unique)]
+    field2: u32,
+    filled: bool,
+}
+
+#[test]
+fn test_retain() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..10 {
+        map.insert(TestElement {
+            field1: i,
+            field2: i % 3,
+            filled: i % 2 == 0,
+        });
+    }
+
+    map.retain(|e| !e.filled);
+
+    assert_eq!(map.len(), 5);
+    assert!(map.iter().all(|(_, e)| !e.filled));
+    assert!(map.get_by_field1(&0).is_none());
+    assert_eq!(map.get_by_field1(&1).unwrap().field2, 1);
+    let field1s = map
+        .get_by_field2(&0)
+        .iter()
+        .map(|e| e.field1)
+        .collect::<Vec<_>>();
+    assert_eq!(field1s, [3, 9]);
+    assert_eq!(map.iter_by_field2().count(), 5);
+}
+
+#[test]
+fn test_extract_if() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..10 {
+        map.insert(TestElement {
+            field1: i,
+            field2: i % 3,
+            filled: i % 2 == 0,
+        });
+    }
+
+    let mut extracted = map
+        .extract_if(|e| e.field2 == 1)
+        .map(|e| e.field1)
+        .collect::<Vec<_>>();
+    extracted.sort();
+    assert_eq!(extracted, [1, 4, 7]);
+
+    assert_eq!(map.len(), 7);
+    assert!(map.get_by_field2(&1).is_empty());
+    assert!(map.get_by_field1(&4).is_none());
+    assert_eq!(map.iter_by_field1().count(), 7);
+}
+
+#[test]
+fn test_extract_if_is_lazy() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..10 {
+        map.insert(TestElement {
+            field1: i,
+            field2: i % 3,
+            filled: i % 2 == 0,
+        });
+    }
+
+    {
+        let mut iter = map.extract_if(|e| e.filled);
+        assert!(iter.next().unwrap().filled);
+    }
+
+    // Only the element yielded before the iterator was dropped is removed.
+    assert_eq!(map.len(), 9);
+    assert_eq!(map.iter().filter(|(_, e)| e.filled).count(), 4);
+
+    // The slot of the removed element can be reused without breaking the lookup tables.
+    map.insert(TestElement {
+        field1: 20,
+        field2: 0,
+        filled: true,
+    });
+    map.retain(|e| e.field1 != 20);
+    assert_eq!(map.len(), 9);
+    assert!(map.get_by_field1(&20).is_none());
+}
@@ -0,0 +1,129 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Debug, Clone, PartialEq)]
+#[multi_index_derive(Debug, Clone)]
+#[multi_index(sequenced)]
+#[multi_index_handles(generational)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    field1: u32,
+    #[multi_index(ordered_non_unique)]
+    field2: u32,
+}
+
+fn elem(field1: u32) -> TestElement {
+    TestElement {
+        field1,
+        field2: field1 % 2,
+    }
+}
+
+fn sequence(map: &MultiIndexTestElementMap) -> Vec<u32> {
+    map.iter_sequenced().map(|e| e.field1).collect()
+}
+
+#[test]
+fn test_insertion_order_survives_slot_reuse() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..4 {
+        map.insert(elem(i));
+    }
+    map.remove_by_field1(&1);
+
+    // The new element reuses the vacant slot of the removed one, but is still last in the sequence.
+    map.insert(elem(4));
+    assert_eq!(sequence(&map), [0, 2, 3, 4]);
+
+    let rev = map
+        .iter_sequenced()
+        .rev()
+        .map(|e| e.field1)
+        .collect::<Vec<_>>();
+    assert_eq!(rev, [4, 3, 2, 0]);
+    assert_eq!(map.iter_sequenced().len(), 4);
+}
+
+#[test]
+fn test_push_and_pop() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.push_back(elem(1)).unwrap();
+    map.push_front(elem(0)).unwrap();
+    map.push_back(elem(2)).unwrap();
+    assert_eq!(sequence(&map), [0, 1, 2]);
+
+    let err = map.push_front(elem(1)).unwrap_err();
+    assert_eq!(err.index, "field1");
+    assert_eq!(sequence(&map), [0, 1, 2]);
+
+    assert_eq!(map.pop_front().unwrap().field1, 0);
+    assert_eq!(map.pop_back().unwrap().field1, 2);
+    assert!(map.get_by_field1(&2).is_none());
+    assert_eq!(map.get_by_field2(&0).len(), 0);
+    assert_eq!(sequence(&map), [1]);
+
+    assert_eq!(map.pop_back().unwrap().field1, 1);
+    assert!(map.pop_front().is_none());
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_relocate() {
+    let mut map = MultiIndexTestElementMap::default();
+    let keys = (0..4).map(|i| map.insert(elem(i))).collect::<Vec<_>>();
+
+    assert!(map.relocate(keys[3], Some(keys[0])));
+    assert_eq!(sequence(&map), [3, 0, 1, 2]);
+
+    assert!(map.relocate(keys[3], None));
+    assert_eq!(sequence(&map), [0, 1, 2, 3]);
+
+    assert!(map.relocate(keys[1], Some(keys[1])));
+    assert_eq!(sequence(&map), [0, 1, 2, 3]);
+
+    map.remove(keys[2]);
+    assert!(!map.relocate(keys[2], None));
+    assert!(!map.relocate(keys[0], Some(keys[2])));
+    assert_eq!(sequence(&map), [0, 1, 3]);
+}
+
+#[test]
+fn test_sequence_consistent_with_other_removals() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..6 {
+        map.insert(elem(i));
+    }
+
+    map.remove_by_field2(&0);
+    assert_eq!(sequence(&map), [1, 3, 5]);
+
+    map.retain(|e| e.field1 != 3);
+    assert_eq!(sequence(&map), [1, 5]);
+
+    let cloned = map.clone();
+    map.clear();
+    assert_eq!(sequence(&map), [] as [u32; 0]);
+    assert_eq!(sequence(&cloned), [1, 5]);
+
+    map.insert(elem(7));
+    map.insert(elem(6));
+    assert_eq!(sequence(&map), [7, 6]);
+    assert_eq!(map.drain().count(), 2);
+    assert_eq!(map.iter_sequenced().count(), 0);
+}
+
+#[test]
+fn test_split_off_keeps_sequence() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.push_back(elem(9)).unwrap();
+    map.push_back(elem(4)).unwrap();
+    map.push_back(elem(3)).unwrap();
+    map.push_front(elem(1)).unwrap();
+    map.push_front(elem(5)).unwrap();
+    map.push_back(elem(7)).unwrap();
+    assert_eq!(sequence(&map), [5, 1, 9, 4, 3, 7]);
+
+    // The moved elements are in a different order in the backing storage and in the index split on.
+    let other = map.split_off_by_field2(&1);
+    assert_eq!(sequence(&other), [5, 1, 9, 3, 7]);
+    assert_eq!(sequence(&map), [4]);
+}
@@ -0,0 +1,224 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+struct Account {
+    #[multi_index(hashed_unique)]
+    id: u32,
+    #[multi_index(hashed_unique, sparse)]
+    external_id: Option<String>,
+    #[multi_index(ordered_non_unique, sparse)]
+    referrer: Option<u32>,
+    balance: u64,
+}
+
+#[test]
+fn test_only_present_values_are_indexed() {
+    let mut map = MultiIndexAccountMap::default();
+    for (id, external_id, referrer) in [
+        (1, Some("a"), None),
+        (2, None, Some(1)),
+        (3, None, Some(1)),
+        (4, Some("b"), Some(2)),
+    ] {
+        map.insert(Account {
+            id,
+            external_id: external_id.map(str::to_string),
+            referrer,
+            balance: 0,
+        });
+    }
+
+    assert_eq!(map.len(), 4);
+    assert_eq!(map.get_by_external_id(&"a".to_string()).unwrap().id, 1);
+    assert_eq!(map.get_by_external_id(&"b".to_string()).unwrap().id, 4);
+    assert_eq!(map.get_by_referrer(&1).len(), 2);
+    assert_eq!(
+        map.iter_by_referrer().map(|a| a.id).collect::<Vec<_>>(),
+        [2, 3, 4]
+    );
+}
+
+#[test]
+fn test_uniqueness_only_among_present_values() {
+    let mut map = MultiIndexAccountMap::default();
+    for (id, external_id, referrer) in [
+        (1, Some("a"), None),
+        (2, None, Some(1)),
+        (3, None, Some(1)),
+        (4, Some("b"), Some(2)),
+    ] {
+        map.insert(Account {
+            id,
+            external_id: external_id.map(str::to_string),
+            referrer,
+            balance: 0,
+        });
+    }
+
+    // Any number of elements may have no value.
+    map.try_insert(Account {
+        id: 5,
+        external_id: None,
+        referrer: None,
+        balance: 0,
+    })
+    .unwrap();
+
+    let res = map.try_insert(Account {
+        id: 6,
+        external_id: Some("a".to_string()),
+        referrer: None,
+        balance: 0,
+    });
+    assert_eq!(res.unwrap_err().index, "external_id");
+    assert_eq!(map.len(), 5);
+}
+
+#[test]
+fn test_modify_between_some_and_none() {
+    let mut map = MultiIndexAccountMap::default();
+    for (id, external_id, referrer) in [
+        (1, Some("a"), None),
+        (2, None, Some(1)),
+        (3, None, Some(1)),
+        (4, Some("b"), Some(2)),
+    ] {
+        map.insert(Account {
+            id,
+            external_id: external_id.map(str::to_string),
+            referrer,
+            balance: 0,
+        });
+    }
+
+    map.modify_by_id(&1, |a| a.external_id = None);
+    assert!(map.get_by_external_id(&"a".to_string()).is_none());
+
+    map.modify_by_id(&2, |a| {
+        a.external_id = Some("a".to_string());
+        a.referrer = None;
+    });
+    assert_eq!(map.get_by_external_id(&"a".to_string()).unwrap().id, 2);
+    assert_eq!(map.get_by_referrer(&1).len(), 1);
+
+    map.modify_by_external_id(&"b".to_string(), |a| {
+        a.external_id = Some("c".to_string());
+        a.referrer = Some(3);
+    });
+    assert!(map.get_by_external_id(&"b".to_string()).is_none());
+    assert_eq!(map.get_by_external_id(&"c".to_string()).unwrap().id, 4);
+    assert_eq!(
+        map.iter_by_referrer().map(|a| a.id).collect::<Vec<_>>(),
+        [3, 4]
+    );
+}
+
+#[test]
+fn test_try_modify_rolls_back() {
+    let mut map = MultiIndexAccountMap::default();
+    for (id, external_id, referrer) in [
+        (1, Some("a"), None),
+        (2, None, Some(1)),
+        (3, None, Some(1)),
+        (4, Some("b"), Some(2)),
+    ] {
+        map.insert(Account {
+            id,
+            external_id: external_id.map(str::to_string),
+            referrer,
+            balance: 0,
+        });
+    }
+
+    let res = map.try_modify_by_id(&2, |a| {
+        a.external_id = Some("b".to_string());
+        a.referrer = None;
+    });
+    assert_eq!(res.unwrap_err().index, "external_id");
+
+    let account = map.get_by_id(&2).unwrap();
+    assert_eq!(account.external_id, None);
+    assert_eq!(account.referrer, Some(1));
+    assert_eq!(map.get_by_referrer(&1).len(), 2);
+    assert_eq!(map.get_by_external_id(&"b".to_string()).unwrap().id, 4);
+}
+
+#[test]
+fn test_remove() {
+    let mut map = MultiIndexAccountMap::default();
+    for (id, external_id, referrer) in [
+        (1, Some("a"), None),
+        (2, None, Some(1)),
+        (3, None, Some(1)),
+        (4, Some("b"), Some(2)),
+    ] {
+        map.insert(Account {
+            id,
+            external_id: external_id.map(str::to_string),
+            referrer,
+            balance: 0,
+        });
+    }
+
+    // Removing an element without a value leaves the sparse lookup tables alone.
+    map.remove_by_id(&3);
+    assert_eq!(map.get_by_referrer(&1).len(), 1);
+
+    let removed = map.remove_by_external_id(&"b".to_string()).unwrap();
+    assert_eq!(removed.id, 4);
+    assert!(map.get_by_referrer(&2).is_empty());
+
+    let removed = map.remove_by_referrer(&1);
+    assert_eq!(removed.len(), 1);
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get_by_external_id(&"a".to_string()).unwrap().id, 1);
+}
+
+#[test]
+fn test_vacant_entry_insert() {
+    let mut map = MultiIndexAccountMap::default();
+    map.insert(Account {
+        id: 1,
+        external_id: Some("a".to_string()),
+        referrer: None,
+        balance: 0,
+    });
+
+    // An element without a value does not have the key of the entry.
+    let MultiIndexAccountMapExternalIdEntry::Vacant(vacant) =
+        map.entry_by_external_id("b".to_string())
+    else {
+        panic!("Expected a vacant entry");
+    };
+    let res = vacant.insert(Account {
+        id: 2,
+        external_id: None,
+        referrer: None,
+        balance: 0,
+    });
+    assert!(matches!(
+        res,
+        Err(multi_index_map::VacantEntryError::KeyMismatch(Account {
+            id: 2,
+            ..
+        }))
+    ));
+    assert_eq!(map.len(), 1);
+
+    let MultiIndexAccountMapExternalIdEntry::Vacant(vacant) =
+        map.entry_by_external_id("b".to_string())
+    else {
+        panic!("Expected a vacant entry");
+    };
+    let inserted = vacant
+        .insert(Account {
+            id: 2,
+            external_id: Some("b".to_string()),
+            referrer: None,
+            balance: 0,
+        })
+        .unwrap();
+    assert_eq!(inserted.id, 2);
+    assert_eq!(map.get_by_external_id(&"b".to_string()).unwrap().id, 2);
+}
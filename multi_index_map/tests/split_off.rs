@@ -0,0 +1,97 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+struct Order {
+    #[multi_index(hashed_unique)]
+    order_id: u32,
+    #[multi_index(ordered_unique)]
+    timestamp: u64,
+    #[multi_index(ordered_non_unique)]
+    trader_name: String,
+}
+
+#[test]
+fn test_split_off_by_unique() {
+    let mut map = MultiIndexOrderMap::default();
+    for i in 0..6 {
+        map.insert(Order {
+            order_id: i,
+            timestamp: 100 + u64::from(i),
+            trader_name: format!("trader{}", i % 3),
+        });
+    }
+
+    let newer = map.split_off_by_timestamp(&103);
+    let ids = map
+        .iter_by_timestamp()
+        .map(|o| o.order_id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids, [0, 1, 2]);
+    let ids = newer
+        .iter_by_timestamp()
+        .map(|o| o.order_id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids, [3, 4, 5]);
+
+    assert!(map.get_by_order_id(&3).is_none());
+    assert_eq!(newer.get_by_order_id(&3).unwrap().timestamp, 103);
+    assert_eq!(map.get_by_trader_name("trader0").len(), 1);
+    assert_eq!(newer.get_by_trader_name("trader0").len(), 1);
+}
+
+#[test]
+fn test_split_off_by_non_unique() {
+    let mut map = MultiIndexOrderMap::default();
+    for i in 0..6 {
+        map.insert(Order {
+            order_id: i,
+            timestamp: 100 + u64::from(i),
+            trader_name: format!("trader{}", i % 3),
+        });
+    }
+
+    let split = map.split_off_by_trader_name("trader1");
+    assert_eq!(map.len(), 2);
+    assert_eq!(split.len(), 4);
+    assert!(map.keys_by_trader_name().all(|name| name == "trader0"));
+    assert_eq!(split.get_by_trader_name("trader2").len(), 2);
+
+    let empty = map.split_off_by_trader_name("trader9");
+    assert!(empty.is_empty());
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn test_append() {
+    let mut map = MultiIndexOrderMap::default();
+    for i in 0..6 {
+        map.insert(Order {
+            order_id: i,
+            timestamp: 100 + u64::from(i),
+            trader_name: format!("trader{}", i % 3),
+        });
+    }
+    let mut other = map.split_off_by_timestamp(&104);
+    other.insert(Order {
+        order_id: 1,
+        timestamp: 200,
+        trader_name: "trader1".to_string(),
+    });
+    other.insert(Order {
+        order_id: 10,
+        timestamp: 210,
+        trader_name: "trader1".to_string(),
+    });
+
+    let errors = map.append(&mut other);
+    assert!(other.is_empty());
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].index, "order_id");
+    assert_eq!(errors[0].elem.timestamp, 200);
+    assert_eq!(map.get(errors[0].existing).unwrap().timestamp, 101);
+
+    assert_eq!(map.len(), 7);
+    assert_eq!(map.get_by_order_id(&10).unwrap().timestamp, 210);
+    assert_eq!(map.get_by_trader_name("trader1").len(), 3);
+}
@@ -0,0 +1,158 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+#[multi_index(ordered_unique, fields(field2, field3))]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    field1: u32,
+    #[multi_index(ordered_non_unique)]
+    field2: u32,
+    #[multi_index(hashed_non_unique)]
+    field3: String,
+}
+
+fn assert_unchanged(map: &MultiIndexTestElementMap) {
+    assert_eq!(map.len(), 6);
+    for i in 0..6 {
+        let elem = map.get_by_field1(&i).unwrap();
+        assert_eq!(elem.field2, i % 2);
+        assert_eq!(elem.field3, i.to_string());
+        assert_eq!(
+            map.get_by_field2_field3(&(i % 2, i.to_string()))
+                .unwrap()
+                .field1,
+            i
+        );
+        assert_eq!(map.get_by_field3(&i.to_string()).len(), 1);
+    }
+    assert_eq!(map.get_by_field2(&0).len(), 3);
+    assert_eq!(map.get_by_field2(&1).len(), 3);
+}
+
+#[test]
+fn test_try_modify_success() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..6 {
+        map.insert(TestElement {
+            field1: i,
+            field2: i % 2,
+            field3: i.to_string(),
+        });
+    }
+
+    let elem = map
+        .try_modify_by_field1(&0, |e| {
+            e.field1 = 10;
+            e.field3 = "ten".to_string();
+        })
+        .unwrap()
+        .unwrap();
+    assert_eq!(elem.field1, 10);
+    assert!(map.get_by_field1(&0).is_none());
+    assert_eq!(map.get_by_field3("ten").len(), 1);
+
+    assert!(map.try_modify_by_field1(&99, |e| e.field1 = 1).unwrap().is_none());
+}
+
+#[test]
+fn test_try_modify_unique_violation_rolls_back() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..6 {
+        map.insert(TestElement {
+            field1: i,
+            field2: i % 2,
+            field3: i.to_string(),
+        });
+    }
+
+    let err = map
+        .try_modify_by_field1(&0, |e| {
+            e.field2 = 1;
+            e.field3 = "x".to_string();
+            e.field1 = 1;
+        })
+        .unwrap_err();
+    assert_eq!(err.index, "field1");
+    assert_unchanged(&map);
+
+    let err = map
+        .try_modify_by_field1(&0, |e| {
+            e.field2 = 1;
+            e.field3 = "1".to_string();
+        })
+        .unwrap_err();
+    assert_eq!(err.index, "field2_field3");
+    assert_eq!(
+        err.to_string(),
+        "Unable to modify element, uniqueness constraint violated on index 'field2_field3'"
+    );
+    assert_unchanged(&map);
+}
+
+#[test]
+fn test_try_modify_non_unique_violation_rolls_back_all() {
+    let mut map = MultiIndexTestElementMap::default();
+    for i in 0..6 {
+        map.insert(TestElement {
+            field1: i,
+            field2: i % 2,
+            field3: i.to_string(),
+        });
+    }
+
+    // The first matching element moves to an unused key, freeing its old one for the second element,
+    //   then the third element collides, so both earlier modifications must be undone in reverse order.
+    let mut next = 0;
+    let err = map
+        .try_modify_by_field2(&0, |e| {
+            e.field1 = [100, 0, 1][next];
+            next += 1;
+        })
+        .unwrap_err();
+    assert_eq!(err.index, "field1");
+    assert_unchanged(&map);
+    assert!(map.get_by_field1(&100).is_none());
+
+    let refs = map
+        .try_modify_by_field2(&0, |e| e.field3.push('!'))
+        .unwrap();
+    assert_eq!(refs.len(), 3);
+    assert_eq!(map.get_by_field3("0!").len(), 1);
+    assert_eq!(map.get_by_field3("2!").len(), 1);
+}
+
+#[derive(MultiIndexMap, Clone, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+struct TestUnindexedElement {
+    #[multi_index(hashed_unique)]
+    field1: u32,
+    field2: String,
+}
+
+#[test]
+fn test_try_modify_violation_restores_unindexed_fields() {
+    let mut map = MultiIndexTestUnindexedElementMap::default();
+    for i in 0..2 {
+        map.insert(TestUnindexedElement {
+            field1: i,
+            field2: i.to_string(),
+        });
+    }
+
+    let err = map
+        .try_modify_by_field1(&0, |e| {
+            e.field2 = "changed".to_string();
+            e.field1 = 1;
+        })
+        .unwrap_err();
+    assert_eq!(err.index, "field1");
+    assert!(format!("{err:?}").starts_with("ModifyError {"));
+    assert_eq!(
+        map.get_by_field1(&0),
+        Some(&TestUnindexedElement {
+            field1: 0,
+            field2: "0".to_string(),
+        })
+    );
+}
@@ -0,0 +1,112 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Clone, Debug)]
+#[multi_index_derive(Debug)]
+#[multi_index(ordered_unique, fields(field2, field3))]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    field1: u32,
+    #[multi_index(ordered_unique)]
+    field2: u32,
+    field3: String,
+}
+
+#[test]
+fn test_insert_error_identifies_index_and_existing() {
+    let mut map = MultiIndexTestElementMap::default();
+    let key1 = map.insert(TestElement {
+        field1: 1,
+        field2: 10,
+        field3: "a".to_string(),
+    });
+    let key2 = map.insert(TestElement {
+        field1: 2,
+        field2: 20,
+        field3: "b".to_string(),
+    });
+
+    let err = map
+        .try_insert(TestElement {
+            field1: 3,
+            field2: 20,
+            field3: "c".to_string(),
+        })
+        .unwrap_err();
+    assert_eq!(err.index, "field2");
+    assert_eq!(err.existing, key2);
+    assert_eq!(err.elem.field1, 3);
+    assert_eq!(
+        err.to_string(),
+        "Unable to insert element, uniqueness constraint violated on index 'field2'"
+    );
+    assert!(format!("{err:?}").starts_with("UniquenessError {"));
+    assert_eq!(map.get(err.existing).unwrap().field1, 2);
+
+    let err = map
+        .try_insert(TestElement {
+            field1: 1,
+            field2: 30,
+            field3: "c".to_string(),
+        })
+        .unwrap_err();
+    assert_eq!(err.index, "field1");
+    assert_eq!(err.existing, key1);
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn test_modify_error_identifies_existing() {
+    let mut map = MultiIndexTestElementMap::default();
+    let key1 = map.insert(TestElement {
+        field1: 1,
+        field2: 10,
+        field3: "a".to_string(),
+    });
+    map.insert(TestElement {
+        field1: 2,
+        field2: 20,
+        field3: "b".to_string(),
+    });
+
+    let err = map
+        .try_modify_by_field1(&2, |e| e.field1 = 1)
+        .unwrap_err();
+    assert_eq!(err.index, "field1");
+    assert_eq!(err.existing, key1);
+}
+
+#[test]
+fn test_errors_work_with_question_mark() {
+    fn insert_all(
+        map: &mut MultiIndexTestElementMap,
+        elems: Vec<TestElement>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for elem in elems {
+            map.try_insert(elem)?;
+        }
+        map.try_modify_by_field2(&10, |e| e.field2 = 20)?;
+        Ok(())
+    }
+
+    let mut map = MultiIndexTestElementMap::default();
+    let err = insert_all(
+        &mut map,
+        vec![
+            TestElement {
+                field1: 1,
+                field2: 10,
+                field3: "a".to_string(),
+            },
+            TestElement {
+                field1: 2,
+                field2: 20,
+                field3: "a".to_string(),
+            },
+        ],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unable to modify element, uniqueness constraint violated on index 'field2'"
+    );
+}
@@ -0,0 +1,140 @@
+use multi_index_map::MultiIndexMap;
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+struct TestElement {
+    #[multi_index(hashed_unique)]
+    field1: u32,
+    #[multi_index(ordered_unique)]
+    field2: u32,
+    #[multi_index(hashed_non_unique)]
+    field3: String,
+}
+
+#[derive(MultiIndexMap, Debug, PartialEq)]
+#[multi_index_derive(Debug)]
+struct TestNonUniqueElement {
+    #[multi_index(hashed_non_unique)]
+    field1: u32,
+}
+
+#[test]
+fn test_upsert_replaces_matching_element() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        field1: 1,
+        field2: 10,
+        field3: "a".to_string(),
+    });
+    map.insert(TestElement {
+        field1: 2,
+        field2: 20,
+        field3: "a".to_string(),
+    });
+
+    let replaced = map
+        .upsert_by_field1(TestElement {
+            field1: 1,
+            field2: 11,
+            field3: "b".to_string(),
+        })
+        .unwrap()
+        .unwrap();
+    assert_eq!(replaced.field2, 10);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get_by_field1(&1).unwrap().field2, 11);
+    assert!(map.get_by_field2(&10).is_none());
+    assert_eq!(map.get_by_field3("a").len(), 1);
+    assert_eq!(map.get_by_field3("b").len(), 1);
+
+    let replaced = map
+        .upsert_by_field2(TestElement {
+            field1: 3,
+            field2: 30,
+            field3: "c".to_string(),
+        })
+        .unwrap();
+    assert!(replaced.is_none());
+    assert_eq!(map.len(), 3);
+}
+
+#[test]
+fn test_upsert_rejects_collision_on_other_index() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        field1: 1,
+        field2: 10,
+        field3: "a".to_string(),
+    });
+    map.insert(TestElement {
+        field1: 2,
+        field2: 20,
+        field3: "a".to_string(),
+    });
+
+    let err = map
+        .upsert_by_field1(TestElement {
+            field1: 1,
+            field2: 20,
+            field3: "b".to_string(),
+        })
+        .unwrap_err();
+    assert_eq!(err.index, "field2");
+    assert_eq!(map.get(err.existing).unwrap().field1, 2);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get_by_field1(&1).unwrap().field2, 10);
+}
+
+#[test]
+fn test_insert_or_replace() {
+    let mut map = MultiIndexTestElementMap::default();
+    map.insert(TestElement {
+        field1: 1,
+        field2: 10,
+        field3: "a".to_string(),
+    });
+    map.insert(TestElement {
+        field1: 2,
+        field2: 20,
+        field3: "a".to_string(),
+    });
+
+    // Collides with the first element on field1 and the second on field2.
+    let mut displaced = map.insert_or_replace(TestElement {
+        field1: 1,
+        field2: 20,
+        field3: "b".to_string(),
+    });
+    displaced.sort_by_key(|e| e.field1);
+    assert_eq!(displaced.len(), 2);
+    assert_eq!(displaced[0].field1, 1);
+    assert_eq!(displaced[1].field1, 2);
+    assert_eq!(map.len(), 1);
+    assert!(map.get_by_field3("a").is_empty());
+    assert_eq!(map.get_by_field2(&20).unwrap().field3, "b");
+
+    // Collides with the same element on both indexes.
+    let displaced = map.insert_or_replace(TestElement {
+        field1: 1,
+        field2: 20,
+        field3: "c".to_string(),
+    });
+    assert_eq!(displaced.len(), 1);
+    assert_eq!(displaced[0].field3, "b");
+
+    let displaced = map.insert_or_replace(TestElement {
+        field1: 5,
+        field2: 50,
+        field3: "c".to_string(),
+    });
+    assert!(displaced.is_empty());
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn test_insert_or_replace_without_unique_indexes() {
+    let mut map = MultiIndexTestNonUniqueElementMap::default();
+    assert!(map.insert_or_replace(TestNonUniqueElement { field1: 1 }).is_empty());
+    assert!(map.insert_or_replace(TestNonUniqueElement { field1: 1 }).is_empty());
+    assert_eq!(map.len(), 2);
+}
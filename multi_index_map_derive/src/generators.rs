@@ -1,5 +1,6 @@
 use ::quote::{format_ident, quote};
-use ::syn::{Field, Visibility, parse_quote};
+use ::syn::{parse_quote, Field, Visibility};
+use convert_case::Casing;
 use proc_macro2::Ident;
 use syn::{Generics, Type};
 
@@ -14,6 +15,94 @@ pub(crate) struct FieldIdents {
     pub(crate) index_name: Ident,
     pub(crate) cloned_name: Ident,
     pub(crate) iter_name: Ident,
+    pub(crate) range_iter_name: Ident,
+    pub(crate) entry_name: Ident,
+    pub(crate) vacant_entry_name: Ident,
+    pub(crate) groups_name: Ident,
+    // The marker type calling the compare function of an ordered index, only generated if it has one.
+    pub(crate) comparator_name: Ident,
+    // The element fields making up the key of this index.
+    // This is just `name` for a regular field index, or each listed field for a composite index.
+    pub(crate) key_fields: Vec<Ident>,
+    // The order-statistic tree kept alongside the lookup table of an `ordered_ranked` index, None otherwise.
+    pub(crate) ranks_name: Option<Ident>,
+    // How to compute the key of a computed key index, None otherwise.
+    // For these indexes, `key_fields` are the fields the key is computed from.
+    pub(crate) computed: Option<ComputedKeyIdents>,
+    // How to decide whether an element belongs in a partial index, None if every element does.
+    pub(crate) filter: Option<FilterIdents>,
+    // Set for a `sparse` index on an `Option<T>` field, whose lookup table only holds the `Some` values, keyed
+    //   by the inner value. The clone taken by pre_modifies is still of the whole `Option<T>`.
+    pub(crate) sparse: bool,
+    // Name of whether the element was in the lookup table before a modification, for partial and sparse indexes.
+    pub(crate) was_indexed_name: Ident,
+    // The type of the collection field of a `hashed_multi` or `ordered_multi` index, None otherwise.
+    // The lookup table of these indexes is keyed on the item type, and holds each element under every item
+    //   of its collection, while the clone taken by pre_modifies is of the whole collection.
+    pub(crate) multi: Option<Type>,
+    // The wrapper around the keys of the lookup table of an index with a custom equivalence, None otherwise.
+    pub(crate) key_wrapper: Option<KeyWrapper>,
+}
+
+// Wrapper type around the keys of a lookup table, so that it compares keys with a custom equivalence rather than the
+//   key type's own impls, eg. `::multi_index_map::Equivalent<::multi_index_map::AsciiCaseInsensitive, String>`.
+// Accessors still take and return the unwrapped keys, which are wrapped at each use of the lookup table.
+pub(crate) struct KeyWrapper {
+    // Path to the wrapper type, which takes the marker type and then the key type as type arguments.
+    pub(crate) path: syn::Path,
+    pub(crate) marker: Type,
+    // Whether the marker is a descending order, whose ranges are given in ascending order of the keys.
+    pub(crate) reversed: bool,
+}
+
+impl KeyWrapper {
+    fn ty(&self, key_ty: &impl ::quote::ToTokens) -> ::proc_macro2::TokenStream {
+        let path = &self.path;
+        let marker = &self.marker;
+        quote! { #path<#marker, #key_ty> }
+    }
+}
+
+pub(crate) struct ComputedKeyIdents {
+    // Path to the user's key function, with any leading `Self` resolved to the element type.
+    pub(crate) key_fn: ::proc_macro2::TokenStream,
+}
+
+pub(crate) struct FilterIdents {
+    // Path to the user's filter predicate, with any leading `Self` resolved to the element type.
+    pub(crate) filter_fn: ::proc_macro2::TokenStream,
+    // The fields the filter reads, which are not treated as unindexed fields.
+    pub(crate) source_fields: Vec<Ident>,
+}
+
+impl FieldIdents {
+    pub(crate) fn new(
+        map_name: &Ident,
+        name: &Ident,
+        key_fields: Vec<Ident>,
+        ranked: bool,
+    ) -> Self {
+        let upper_camel_name = name.to_string().to_case(::convert_case::Case::UpperCamel);
+        Self {
+            name: name.clone(),
+            index_name: format_ident!("_{name}_index"),
+            cloned_name: format_ident!("{name}_orig"),
+            iter_name: format_ident!("{map_name}{upper_camel_name}Iter"),
+            range_iter_name: format_ident!("{map_name}{upper_camel_name}RangeIter"),
+            entry_name: format_ident!("{map_name}{upper_camel_name}Entry"),
+            vacant_entry_name: format_ident!("{map_name}{upper_camel_name}VacantEntry"),
+            groups_name: format_ident!("{map_name}{upper_camel_name}Groups"),
+            comparator_name: format_ident!("{map_name}{upper_camel_name}Compare"),
+            key_fields,
+            ranks_name: ranked.then(|| format_ident!("_{name}_ranks")),
+            computed: None,
+            filter: None,
+            sparse: false,
+            was_indexed_name: format_ident!("{name}_was_indexed"),
+            multi: None,
+            key_wrapper: None,
+        }
+    }
 }
 
 struct FieldInfo<'a> {
@@ -25,6 +114,222 @@ struct FieldInfo<'a> {
 pub(crate) const EXPECT_NAMED_FIELDS: &str =
     "Internal logic broken, all fields should have named identifiers";
 
+// TokenStream representing whether the element `elem` belongs in the lookup table of this index,
+//   or None if this is neither a partial nor a sparse index, so every element does.
+fn filter_call(idents: &FieldIdents, elem: &Ident) -> Option<::proc_macro2::TokenStream> {
+    let filter = idents.filter.as_ref().map(|filter| {
+        let filter_fn = &filter.filter_fn;
+        quote! { #filter_fn(&#elem) }
+    });
+    let present = idents.sparse.then(|| {
+        let key_fields = &idents.key_fields;
+        quote! { #(#elem.#key_fields)*.is_some() }
+    });
+    match (present, filter) {
+        (Some(present), Some(filter)) => Some(quote! { (#present && #filter) }),
+        (present, filter) => present.or(filter),
+    }
+}
+
+// TokenStream representing a reference to the inner value of the field of a sparse index in the element `elem`.
+// Only used once filter_call has established that the field is present.
+fn sparse_key_ref(idents: &FieldIdents, elem: &Ident) -> ::proc_macro2::TokenStream {
+    let key_fields = &idents.key_fields;
+    sparse_unwrap(idents, quote! { #(#elem.#key_fields)* })
+}
+
+// TokenStream representing a reference to the inner value of `option`, the field of a sparse index.
+fn sparse_unwrap(idents: &FieldIdents, option: ::proc_macro2::TokenStream) -> ::proc_macro2::TokenStream {
+    let error_msg = format!(
+        "Unable to get key of element, field '{}' of sparse index is None",
+        idents.name
+    );
+    quote! { #option.as_ref().expect(#error_msg) }
+}
+
+// TokenStream representing the path to the key function of a computed key index, or the predicate of a
+//   partial index, as given in the attribute.
+// The attribute is on the element struct, so a leading `Self` refers to the element type, whereas in the
+//   generated code `Self` is the map, so it is replaced with the element type.
+pub(crate) fn resolve_fn_path(
+    key: &syn::Path,
+    element_name: &Ident,
+    generics: &Generics,
+) -> ::proc_macro2::TokenStream {
+    let mut segments = key.segments.iter();
+    match segments.next() {
+        Some(first) if key.leading_colon.is_none() && first.ident == "Self" => {
+            let (_, types, _) = generics.split_for_impl();
+            quote! { <#element_name #types>#(::#segments)* }
+        }
+        _ => quote! { #key },
+    }
+}
+
+// TokenStream representing an owned clone of the key of this index, taken from the element `elem`.
+// For composite indexes this is a tuple of clones of each key field.
+// For computed key indexes this is the result of the key function, which takes the element by reference.
+fn key_clone(idents: &FieldIdents, elem: &Ident) -> ::proc_macro2::TokenStream {
+    if idents.sparse {
+        let key = sparse_key_ref(idents, elem);
+        return quote! { #key.clone() };
+    }
+    if let Some(computed) = &idents.computed {
+        let key_fn = &computed.key_fn;
+        return quote! { #key_fn(&#elem) };
+    }
+    let key_fields = &idents.key_fields;
+    match key_fields.as_slice() {
+        [field] => quote! { #elem.#field.clone() },
+        _ => quote! { (#(#elem.#key_fields.clone(),)*) },
+    }
+}
+
+// TokenStream representing a reference to the key of this index, taken from the element `elem`,
+//   suitable for passing to the lookup table's get or remove methods.
+fn key_ref(idents: &FieldIdents, elem: &Ident) -> ::proc_macro2::TokenStream {
+    if idents.sparse {
+        return sparse_key_ref(idents, elem);
+    }
+    if let Some(computed) = &idents.computed {
+        let key_fn = &computed.key_fn;
+        return quote! { &#key_fn(&#elem) };
+    }
+    let key_fields = &idents.key_fields;
+    match key_fields.as_slice() {
+        [field] => quote! { &#elem.#field },
+        _ => quote! { &(#(#elem.#key_fields.clone(),)*) },
+    }
+}
+
+// TokenStream representing whether the key of this index in the element `elem` differs from the
+//   key `orig` previously cloned by pre_modifies.
+// Composite keys are compared as tuples of references, to avoid cloning the new key unless it changed.
+fn key_changed(idents: &FieldIdents, elem: &Ident, orig: &Ident) -> ::proc_macro2::TokenStream {
+    if let Some(computed) = &idents.computed {
+        let key_fn = &computed.key_fn;
+        return quote! { #key_fn(&#elem) != #orig };
+    }
+    let key_fields = &idents.key_fields;
+    match key_fields.as_slice() {
+        [field] => quote! { #elem.#field != #orig },
+        _ => {
+            let positions = (0..key_fields.len()).map(syn::Index::from);
+            quote! { (#(&#elem.#key_fields,)*) != (#(&#orig.#positions,)*) }
+        }
+    }
+}
+
+// TokenStream representing the type of the keys of the lookup table of this index, whose key type is `ty`.
+fn lookup_key_type(idents: &FieldIdents, ty: &impl ::quote::ToTokens) -> ::proc_macro2::TokenStream {
+    match &idents.key_wrapper {
+        Some(wrapper) => wrapper.ty(ty),
+        None => quote! { #ty },
+    }
+}
+
+// TokenStream representing the owned key `key` wrapped for the lookup table of this index.
+fn wrap_key(idents: &FieldIdents, key: ::proc_macro2::TokenStream) -> ::proc_macro2::TokenStream {
+    match &idents.key_wrapper {
+        Some(wrapper) => {
+            let wrapped = wrapper.ty(&quote! { _ });
+            quote! { <#wrapped>::new(#key) }
+        }
+        None => key,
+    }
+}
+
+// TokenStream representing the reference to a key `key` wrapped for the lookup table of this index,
+//   suitable for passing to the lookup table's get or remove methods.
+fn wrap_key_ref(idents: &FieldIdents, key: ::proc_macro2::TokenStream) -> ::proc_macro2::TokenStream {
+    match &idents.key_wrapper {
+        Some(wrapper) => {
+            let wrapped = wrapper.ty(&quote! { _ });
+            quote! { <#wrapped>::from_ref(#key) }
+        }
+        None => key,
+    }
+}
+
+// TokenStream representing the owned key of type `ty` inside `key`, an owned key of the lookup table of this index.
+fn unwrap_key(idents: &FieldIdents, key: ::proc_macro2::TokenStream) -> ::proc_macro2::TokenStream {
+    match &idents.key_wrapper {
+        Some(_) => quote! { #key.into_key() },
+        None => key,
+    }
+}
+
+// TokenStream representing the reference to a key of the lookup table of this index `key`, unwrapped to the key type.
+fn unwrap_key_ref(idents: &FieldIdents, key: ::proc_macro2::TokenStream) -> ::proc_macro2::TokenStream {
+    match &idents.key_wrapper {
+        Some(_) => quote! { #key.as_key() },
+        None => key,
+    }
+}
+
+// TokenStream representing the bounds of the range `range` wrapped for the lookup table of this index.
+// An inverted range is replaced by empty bounds, as BTreeMap::range would panic on it.
+fn wrap_range(idents: &FieldIdents, range: ::proc_macro2::TokenStream) -> ::proc_macro2::TokenStream {
+    match &idents.key_wrapper {
+        Some(wrapper) if wrapper.reversed => {
+            let marker = &wrapper.marker;
+            quote! { ::multi_index_map::comparator::wrap_reversed_bounds::<#marker, _>(&#range) }
+        }
+        Some(wrapper) => {
+            let marker = &wrapper.marker;
+            quote! { ::multi_index_map::comparator::wrap_bounds::<#marker, _>(&#range) }
+        }
+        None => quote! { ::multi_index_map::range_bounds(&#range) },
+    }
+}
+
+// TokenStream representing the where-clause bounds on `__MultiIndexMapKeyType`, the borrowed form of the key type
+//   `ty` accepted by the generic accessors of this index.
+// With a key wrapper, the wrapped key type must borrow as the wrapped borrowed form, rather than the key type as the
+//   borrowed form, as each lookup wraps the borrowed key.
+fn borrowed_key_bounds(idents: &FieldIdents, ty: &Type, ordering: &Ordering) -> ::proc_macro2::TokenStream {
+    let key_bound = match ordering {
+        Ordering::Hashed => quote! { ::std::hash::Hash + Eq },
+        Ordering::Ordered => quote! { Ord },
+    };
+    match &idents.key_wrapper {
+        Some(wrapper) => {
+            let wrapped_ty = wrapper.ty(ty);
+            let wrapped_borrowed = wrapper.ty(&quote! { __MultiIndexMapKeyType });
+            quote! {
+                #wrapped_ty: ::std::borrow::Borrow<#wrapped_borrowed>,
+                #wrapped_borrowed: #key_bound,
+                __MultiIndexMapKeyType: ?Sized,
+            }
+        }
+        None => quote! {
+            #ty: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
+            __MultiIndexMapKeyType: #key_bound + ?Sized,
+        },
+    }
+}
+
+// TokenStream representing an iterator over references to the items of the collection field of a multi-valued
+//   index in the element `elem`.
+fn multi_items(idents: &FieldIdents, elem: &Ident) -> ::proc_macro2::TokenStream {
+    let key_fields = &idents.key_fields;
+    quote! { (&#elem #(.#key_fields)*).into_iter() }
+}
+
+// TokenStream representing the key handle for the element at position `idx` in the backing storage.
+// With generational handles, this also reads the current generation of that slot.
+pub(crate) fn key_handle(
+    key_name: &Ident,
+    extra_attrs: &ExtraAttributes,
+    idx: &Ident,
+) -> ::proc_macro2::TokenStream {
+    if extra_attrs.generational_handles {
+        quote! { #key_name(#idx, self._generations[#idx]) }
+    } else {
+        quote! { #key_name(#idx) }
+    }
+}
+
 // IMPORTANT NOTE ABOUT BACKING STORE AND INDEX KEYS
 // -------------------------------------------------
 // The backing storage is a slab (slab::Slab<T>). Slab indices (usize) are stable
@@ -64,19 +369,38 @@ pub(crate) fn generate_lookup_tables<'a>(
     extra_attrs: &'a ExtraAttributes,
 ) -> impl Iterator<Item = ::proc_macro2::TokenStream> + 'a {
     fields.iter().map(|(f, idents, ordering, uniqueness)| {
-        let ty = &f.ty;
-        let index_name = &idents.index_name;
-
-        let field_type = index_field_type(ty, ordering, uniqueness, extra_attrs);
+        let (names, types): (Vec<_>, Vec<_>) =
+            lookup_table_field_types(f, idents, ordering, uniqueness, extra_attrs).unzip();
 
         quote! {
-            #index_name: #field_type,
+            #(#names: #types,)*
         }
     })
 }
 
+// The name and type of each field of the map holding a lookup table of this index.
+// This is the lookup table itself, and the order-statistic tree of an `ordered_ranked` index.
+fn lookup_table_field_types<'a>(
+    f: &Field,
+    idents: &'a FieldIdents,
+    ordering: &Ordering,
+    uniqueness: &Uniqueness,
+    extra_attrs: &ExtraAttributes,
+) -> impl Iterator<Item = (&'a Ident, ::proc_macro2::TokenStream)> {
+    let ty = lookup_key_type(idents, &f.ty);
+    let field_type = index_field_type(&ty, ordering, uniqueness, extra_attrs);
+    let ranks = idents.ranks_name.as_ref().map(|ranks_name| {
+        (
+            ranks_name,
+            quote! { ::multi_index_map::rank_tree::RankTree<#ty> },
+        )
+    });
+
+    std::iter::once((&idents.index_name, field_type)).chain(ranks)
+}
+
 fn index_field_type(
-    ty: &Type,
+    ty: &::proc_macro2::TokenStream,
     ordering: &Ordering,
     uniqueness: &Uniqueness,
     extra_attrs: &ExtraAttributes,
@@ -111,6 +435,11 @@ pub(crate) fn generate_lookup_table_init(
 ) -> impl Iterator<Item = ::proc_macro2::TokenStream> + '_ {
     fields.iter().map(|(_f, idents, ordering, _uniqueness)| {
         let index_name = &idents.index_name;
+        let ranks = idents.ranks_name.as_ref().map(|ranks_name| {
+            quote! {
+                #ranks_name: ::multi_index_map::rank_tree::RankTree::default(),
+            }
+        });
 
         match ordering {
             Ordering::Hashed => quote! {
@@ -118,6 +447,7 @@ pub(crate) fn generate_lookup_table_init(
             },
             Ordering::Ordered => quote! {
                 #index_name: ::std::collections::BTreeMap::new(),
+                #ranks
             },
         }
     })
@@ -162,29 +492,54 @@ pub(crate) fn generate_lookup_table_shrink(
 }
 
 // For each indexed field generate a TokenStream representing getting the Entry for that field's lookup table
-pub(crate) fn generate_entries_for_insert(
-    fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
-) -> impl Iterator<Item = ::proc_macro2::TokenStream> + '_ {
-    fields.iter().map(|(_f, idents, ordering, uniqueness)| {
+// If the entry is occupied, the element is returned in a UniquenessError, along with the name of the field
+//   and the key handle of the element already occupying it, given by `existing_handle`.
+pub(crate) fn generate_entries_for_insert<'a>(
+    fields: &'a [(Field, FieldIdents, Ordering, Uniqueness)],
+    existing_handle: &'a ::proc_macro2::TokenStream,
+) -> impl Iterator<Item = ::proc_macro2::TokenStream> + 'a {
+    let elem = format_ident!("elem");
+    fields.iter().map(move |(_f, idents, ordering, uniqueness)| {
         let field_name = &idents.name;
+        let field_name_string = field_name.to_string();
         let index_name = &idents.index_name;
         let entry_name = format_ident!("{field_name}_entry");
+        let key = wrap_key(idents, key_clone(idents, &elem));
 
-        match uniqueness {
-            Uniqueness::Unique => match ordering {
-                Ordering::Hashed => {
-                    quote! {
-                        let #entry_name = match self.#index_name.entry(elem.#field_name.clone()) {
-                            ::std::collections::hash_map::Entry::Occupied(_) => return Err(::multi_index_map::UniquenessError(elem)),
-                            ::std::collections::hash_map::Entry::Vacant(e) => e,
-                        };
-                    }
+        let uniqueness_error = quote! {
+            {
+                let existing = *e.get();
+                return Err(::multi_index_map::UniquenessError {
+                    elem,
+                    index: #field_name_string,
+                    existing: #existing_handle,
+                });
+            }
+        };
+
+        let entry = match ordering {
+            Ordering::Hashed => quote! {
+                match self.#index_name.entry(#key) {
+                    ::std::collections::hash_map::Entry::Occupied(e) => #uniqueness_error
+                    ::std::collections::hash_map::Entry::Vacant(e) => e,
                 }
-                Ordering::Ordered => quote! {
-                    let #entry_name = match self.#index_name.entry(elem.#field_name.clone()) {
-                        ::std::collections::btree_map::Entry::Occupied(_) => return Err(::multi_index_map::UniquenessError(elem)),
-                        ::std::collections::btree_map::Entry::Vacant(e) => e,
-                    };
+            },
+            Ordering::Ordered => quote! {
+                match self.#index_name.entry(#key) {
+                    ::std::collections::btree_map::Entry::Occupied(e) => #uniqueness_error
+                    ::std::collections::btree_map::Entry::Vacant(e) => e,
+                }
+            },
+        };
+
+        match uniqueness {
+            // Elements not matching the filter of a partial index have no entry in its lookup table.
+            Uniqueness::Unique => match filter_call(idents, &elem) {
+                Some(filter) => quote! {
+                    let #entry_name = if #filter { Some(#entry) } else { None };
+                },
+                None => quote! {
+                    let #entry_name = #entry;
                 },
             },
             Uniqueness::NonUnique => quote! {},
@@ -200,20 +555,56 @@ pub(crate) fn generate_entries_for_insert(
 pub(crate) fn generate_inserts_for_entries(
     fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
 ) -> impl Iterator<Item = ::proc_macro2::TokenStream> + '_ {
-    fields.iter().map(|(_f, idents, _ordering, uniqueness)| {
+    let elem = format_ident!("elem");
+    fields.iter().map(move |(_f, idents, _ordering, uniqueness)| {
         let field_name = &idents.name;
         let index_name = &idents.index_name;
         let entry_name = format_ident!("{field_name}_entry");
+        let key = wrap_key(idents, key_clone(idents, &elem));
+        let ranks_insert = idents.ranks_name.as_ref().map(|ranks_name| {
+            quote! {
+                self.#ranks_name.insert(#key, idx);
+            }
+        });
 
-        match uniqueness {
-            Uniqueness::Unique => quote! {
+        match (uniqueness, filter_call(idents, &elem)) {
+            (Uniqueness::Unique, None) => quote! {
                 #entry_name.insert(idx);
             },
-            Uniqueness::NonUnique => quote! {
-                self.#index_name.entry(elem.#field_name.clone())
-                    .or_insert(::std::collections::BTreeSet::new())
-                    .insert(idx);
+            (Uniqueness::Unique, Some(_)) => quote! {
+                if let Some(#entry_name) = #entry_name {
+                    #entry_name.insert(idx);
+                }
             },
+            (Uniqueness::NonUnique, filter) => {
+                let insert = match &idents.multi {
+                    // The element is added under every item of the collection of a multi-valued index.
+                    Some(_) => {
+                        let items = multi_items(idents, &elem);
+                        quote! {
+                            for item in #items {
+                                self.#index_name.entry(item.clone())
+                                    .or_insert(::std::collections::BTreeSet::new())
+                                    .insert(idx);
+                            }
+                        }
+                    }
+                    None => quote! {
+                        self.#index_name.entry(#key)
+                            .or_insert(::std::collections::BTreeSet::new())
+                            .insert(idx);
+                        #ranks_insert
+                    },
+                };
+                match filter {
+                    Some(filter) => quote! {
+                        if #filter {
+                            #insert
+                        }
+                    },
+                    None => insert,
+                }
+            }
         }
     })
 }
@@ -231,14 +622,35 @@ pub(crate) fn generate_inserts_for_entries(
 //     + If there are more than one indices in the container, remove idx from it
 //     + If there is exactly one index in the container, then the index has to be idx,
 //       remove the key from the lookup table
+//   - When the index is ordered_ranked, also remove (elem_orig.#field_name, idx) from its order-statistic tree
+// When handles are generational, an extra TokenStream is added at the end, which bumps the generation of
+//   the removed element's slot, so that any key handles still referring to it are detected as stale.
+// When the map is sequenced, another is added which unlinks the removed element's slot from the sequence,
+//   and likewise with a random_access index, which removes the slot from the dense sequence of positions.
 pub(crate) fn generate_removes(
     fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
+    extra_attrs: &ExtraAttributes,
 ) -> Vec<::proc_macro2::TokenStream> {
+    let generation_bump = extra_attrs.generational_handles.then(|| {
+        quote! {
+            self._generations[idx] = self._generations[idx].wrapping_add(1);
+        }
+    });
+    let sequence_unlink = extra_attrs.sequenced.then(|| {
+        quote! {
+            self._sequence.remove(idx);
+        }
+    });
+    let random_access_remove = extra_attrs.random_access.then(|| {
+        quote! {
+            self._random_access.remove(idx);
+        }
+    });
+
     fields
         .iter()
         .map(|(_f, idents, _ordering, uniqueness)| {
-            let field_name = &idents.name;
-            let field_name_string = stringify!(field_name);
+            let field_name_string = idents.name.to_string();
             let error_msg = format!(
                 concat!(
                     "Internal invariants broken, ",
@@ -247,13 +659,34 @@ pub(crate) fn generate_removes(
                 field_name_string
             );
             let index_name = &idents.index_name;
+            let key = wrap_key_ref(idents, key_ref(idents, &format_ident!("elem_orig")));
+            let ranks_remove = idents.ranks_name.as_ref().map(|ranks_name| {
+                quote! {
+                    self.#ranks_name.remove(key_to_remove, idx);
+                }
+            });
 
-            match uniqueness {
+            let remove = match uniqueness {
                 Uniqueness::Unique => quote! {
-                    let _removed_elem = self.#index_name.remove(&elem_orig.#field_name);
+                    let _removed_elem = self.#index_name.remove(#key);
                 },
+                // An item may appear more than once in the collection of a multi-valued index,
+                //   so idx may already have been removed from its container.
+                Uniqueness::NonUnique if idents.multi.is_some() => {
+                    let items = multi_items(idents, &format_ident!("elem_orig"));
+                    quote! {
+                        for item in #items {
+                            if let Some(elems) = self.#index_name.get_mut(item) {
+                                elems.remove(&idx);
+                                if elems.is_empty() {
+                                    self.#index_name.remove(item);
+                                }
+                            }
+                        }
+                    }
+                }
                 Uniqueness::NonUnique => quote! {
-                    let key_to_remove = &elem_orig.#field_name;
+                    let key_to_remove = #key;
                     if let Some(elems) = self.#index_name.get_mut(key_to_remove) {
                         if elems.len() > 1 {
                             if !elems.remove(&idx){
@@ -263,10 +696,25 @@ pub(crate) fn generate_removes(
                             self.#index_name.remove(key_to_remove);
                         }
                     }
+                    #ranks_remove
+
+                },
+            };
 
+            // An element not matching the filter of a partial index is not in its lookup table,
+            //   but another element with the same key may be.
+            match filter_call(idents, &format_ident!("elem_orig")) {
+                Some(filter) => quote! {
+                    if #filter {
+                        #remove
+                    }
                 },
+                None => remove,
             }
         })
+        .chain(generation_bump)
+        .chain(sequence_unlink)
+        .chain(random_access_remove)
         .collect()
 }
 
@@ -278,11 +726,23 @@ pub(crate) fn generate_pre_modifies(
     fields
         .iter()
         .map(|(_f, idents, _, _)| {
-            let field_name = &idents.name;
             let orig_ident = &idents.cloned_name;
+            let elem = format_ident!("elem");
+            // The field of a sparse index may be None, so the whole field is cloned rather than the key.
+            let key = match idents.key_fields.as_slice() {
+                [field] if idents.sparse => quote! { #elem.#field.clone() },
+                _ => key_clone(idents, &elem),
+            };
+            let was_indexed = filter_call(idents, &elem).map(|filter| {
+                let was_indexed_name = &idents.was_indexed_name;
+                quote! {
+                    let #was_indexed_name = #filter;
+                }
+            });
 
             quote! {
-                let #orig_ident = elem.#field_name.clone();
+                let #orig_ident = #key;
+                #was_indexed
             }
         })
         .collect::<Vec<_>>()
@@ -300,14 +760,17 @@ pub(crate) fn generate_pre_modifies(
 //   - When the field is non-unique, remove idx from the container associated with the old key
 //     + if the container is empty after removal, remove the old key, and insert idx to the new key
 //       (create a new container if necessary)
+//     + if the index is ordered_ranked, move idx from the old key to the new key in its order-statistic tree
 pub(crate) fn generate_post_modifies(
     fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
 ) -> Vec<::proc_macro2::TokenStream> {
-    fields.iter().map(|(_f, idents, _ordering, uniqueness)| {
-        let field_name = &idents.name;
-        let field_name_string = stringify!(field_name);
+    fields.iter().map(|(_f, idents, ordering, uniqueness)| {
+        let field_name_string = idents.name.to_string();
         let orig_ident = &idents.cloned_name;
         let index_name = &idents.index_name;
+        let elem = format_ident!("elem");
+        let key = wrap_key(idents, key_clone(idents, &elem));
+        let changed = key_changed(idents, &elem, orig_ident);
         let error_msg = format!(
             concat!(
                 "Internal invariants broken, ",
@@ -315,47 +778,243 @@ pub(crate) fn generate_post_modifies(
             ),
             field_name_string
         );
+        if idents.multi.is_some() {
+            return generate_multi_post_modify(idents, ordering, &error_msg);
+        }
 
-        match uniqueness {
-            Uniqueness::Unique => quote! {
-                if elem.#field_name != #orig_ident {
-                    let idx = self.#index_name.remove(&#orig_ident).expect(#error_msg);
-                    let orig_elem_idx = self.#index_name.insert(elem.#field_name.clone(), idx);
+        let orig_key = if idents.sparse {
+            sparse_unwrap(idents, quote! { #orig_ident })
+        } else {
+            quote! { &#orig_ident }
+        };
+        let orig_key = wrap_key_ref(idents, orig_key);
+        let ranks_remove = idents.ranks_name.as_ref().map(|ranks_name| {
+            quote! {
+                self.#ranks_name.remove(#orig_key, idx);
+            }
+        });
+        let ranks_insert = idents.ranks_name.as_ref().map(|ranks_name| {
+            quote! {
+                self.#ranks_name.insert(#key, idx);
+            }
+        });
+
+        let (remove_orig, insert_new) = match uniqueness {
+            Uniqueness::Unique => (
+                quote! {
+                    self.#index_name.remove(#orig_key).expect(#error_msg);
+                },
+                quote! {
+                    let orig_elem_idx = self.#index_name.insert(#key, idx);
                     if orig_elem_idx.is_some() {
                         panic!(
                             "Unable to insert element, uniqueness constraint violated on field '{}'",
                             #field_name_string
                         );
                     }
-                }
-            },
-            Uniqueness::NonUnique => quote! {
-                if elem.#field_name != #orig_ident {
-                    let idxs = self.#index_name.get_mut(&#orig_ident).expect(#error_msg);
+                },
+            ),
+            Uniqueness::NonUnique => (
+                quote! {
+                    let idxs = self.#index_name.get_mut(#orig_key).expect(#error_msg);
                     if idxs.len() > 1 {
                         if !(idxs.remove(&idx)) {
                             panic!(#error_msg);
                         }
                     } else {
-                        self.#index_name.remove(&#orig_ident);
+                        self.#index_name.remove(#orig_key);
                     }
-                    self.#index_name.entry(elem.#field_name.clone())
+                    #ranks_remove
+                },
+                quote! {
+                    self.#index_name.entry(#key)
                         .or_insert(::std::collections::BTreeSet::new())
                         .insert(idx);
+                    #ranks_insert
+                },
+            ),
+        };
+
+        match filter_call(idents, &elem) {
+            // The element leaves or joins the lookup table of a partial index when the result of the filter changes,
+            //   and otherwise moves between keys as usual.
+            Some(filter) => {
+                let was_indexed_name = &idents.was_indexed_name;
+                quote! {
+                    let is_indexed = #filter;
+                    if #was_indexed_name && (!is_indexed || #changed) {
+                        #remove_orig
+                    }
+                    if is_indexed && (!#was_indexed_name || #changed) {
+                        #insert_new
+                    }
+                }
+            }
+            None => quote! {
+                if #changed {
+                    #remove_orig
+                    #insert_new
                 }
             },
         }
     }).collect()
 }
 
+// TokenStream representing the post_modify of a multi-valued index.
+// The items of the original and modified collections are each collected into a set, and idx is only removed from
+//   the containers of items no longer in the collection, and only added under items new to the collection.
+// For a partial index, the set of items is empty while the element does not match the filter.
+fn generate_multi_post_modify(
+    idents: &FieldIdents,
+    ordering: &Ordering,
+    error_msg: &str,
+) -> ::proc_macro2::TokenStream {
+    let orig_ident = &idents.cloned_name;
+    let index_name = &idents.index_name;
+    let elem = format_ident!("elem");
+    let changed = key_changed(idents, &elem, orig_ident);
+    let item_set = match ordering {
+        Ordering::Hashed => quote! { ::std::collections::HashSet },
+        Ordering::Ordered => quote! { ::std::collections::BTreeSet },
+    };
+    // The clone taken by pre_modifies is of the collection itself.
+    let orig_items = quote! { (&#orig_ident).into_iter() };
+    let new_items = multi_items(idents, &elem);
+
+    let diff = quote! {
+        for item in &orig_items {
+            if !new_items.contains(item) {
+                let idxs = self.#index_name.get_mut(*item).expect(#error_msg);
+                idxs.remove(&idx);
+                if idxs.is_empty() {
+                    self.#index_name.remove(*item);
+                }
+            }
+        }
+        for item in &new_items {
+            if !orig_items.contains(item) {
+                self.#index_name.entry((*item).clone())
+                    .or_insert(::std::collections::BTreeSet::new())
+                    .insert(idx);
+            }
+        }
+    };
+
+    match filter_call(idents, &elem) {
+        Some(filter) => {
+            let was_indexed_name = &idents.was_indexed_name;
+            quote! {
+                let is_indexed = #filter;
+                if #was_indexed_name != is_indexed || #changed {
+                    let orig_items: #item_set<_> = if #was_indexed_name { #orig_items.collect() } else { #item_set::new() };
+                    let new_items: #item_set<_> = if is_indexed { #new_items.collect() } else { #item_set::new() };
+                    #diff
+                }
+            }
+        }
+        None => quote! {
+            if #changed {
+                let orig_items: #item_set<_> = #orig_items.collect();
+                let new_items: #item_set<_> = #new_items.collect();
+                #diff
+            }
+        },
+    }
+}
+
+// For each unique indexed field generate a TokenStream representing the check of whether a modification
+//   would violate that field's uniqueness constraint, before any lookup table is changed.
+// Used in try_modify_by_. Run after an element is modified in the backing storage, but before post_modifies.
+// Each check is the start of an if-else chain evaluating to the name of the first violated index, and the
+//   position in the backing storage of the element already holding the new key,
+//   so the checks must be followed by a final `{ None }` block.
+// A changed key may still be equivalent to the old one under a custom equivalence or order, in which case the
+//   element at `idx` finds itself in the lookup table, which is not a violation.
+pub(crate) fn generate_uniqueness_checks(
+    fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
+) -> Vec<::proc_macro2::TokenStream> {
+    let elem = format_ident!("elem");
+    fields
+        .iter()
+        .filter(|(_f, _idents, _ordering, uniqueness)| matches!(uniqueness, Uniqueness::Unique))
+        .map(|(_f, idents, _ordering, _uniqueness)| {
+            let field_name_string = idents.name.to_string();
+            let index_name = &idents.index_name;
+            let changed = key_changed(idents, &elem, &idents.cloned_name);
+            let key = wrap_key_ref(idents, key_ref(idents, &elem));
+            // An element joining the lookup table of a partial index may collide even if its key is unchanged.
+            let needs_check = match filter_call(idents, &elem) {
+                Some(filter) => {
+                    let was_indexed_name = &idents.was_indexed_name;
+                    quote! { #filter && (!#was_indexed_name || #changed) }
+                }
+                None => changed,
+            };
+
+            quote! {
+                if let Some(&existing) = if #needs_check {
+                    self.#index_name.get(#key).filter(|&&existing| existing != idx)
+                } else {
+                    None
+                } {
+                    Some((#field_name_string, existing))
+                } else
+            }
+        })
+        .collect()
+}
+
+// TokenStream representing the position in the backing storage of the element whose key in this unique index
+//   is the same as that of `elem`, if any.
+// An element not matching the filter of a partial index would not be added to its lookup table, so cannot collide.
+fn unique_lookup(idents: &FieldIdents, elem: &Ident) -> ::proc_macro2::TokenStream {
+    let index_name = &idents.index_name;
+    let key = wrap_key_ref(idents, key_ref(idents, elem));
+    match filter_call(idents, elem) {
+        Some(filter) => quote! {
+            if #filter { self.#index_name.get(#key).copied() } else { None }
+        },
+        None => quote! {
+            self.#index_name.get(#key).copied()
+        },
+    }
+}
+
+// For each unique indexed field generate a TokenStream representing a pair of the field name,
+//   and the position in the backing storage of any element whose key collides with that of `elem`.
+// Used by upsert_by_ and insert_or_replace to find every element a new element would collide with.
+pub(crate) fn generate_unique_lookups(
+    fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
+) -> Vec<::proc_macro2::TokenStream> {
+    let elem = format_ident!("elem");
+    fields
+        .iter()
+        .filter(|(_f, _idents, _ordering, uniqueness)| matches!(uniqueness, Uniqueness::Unique))
+        .map(|(_f, idents, _ordering, _uniqueness)| {
+            let field_name_string = idents.name.to_string();
+            let lookup = unique_lookup(idents, &elem);
+
+            quote! {
+                (#field_name_string, #lookup)
+            }
+        })
+        .collect()
+}
+
 pub(crate) fn generate_clears(
     fields: &[(Field, FieldIdents, Ordering, Uniqueness)],
 ) -> impl Iterator<Item = ::proc_macro2::TokenStream> + '_ {
     fields.iter().map(|(_f, idents, _ordering, _uniqueness)| {
         let index_name = &idents.index_name;
+        let ranks_clear = idents.ranks_name.as_ref().map(|ranks_name| {
+            quote! {
+                self.#ranks_name.clear();
+            }
+        });
 
         quote! {
             self.#index_name.clear();
+            #ranks_clear
         }
     })
 }
@@ -377,32 +1036,24 @@ fn generate_field_getter(
     let field_type = &field_info.ty;
     let (_, types, _) = generics.split_for_impl();
 
-    let key_bounds = match ordering {
-        Ordering::Hashed => quote! {
-            __MultiIndexMapKeyType: ::std::hash::Hash + Eq + ?Sized
-        },
-        Ordering::Ordered => quote! {
-            __MultiIndexMapKeyType: Ord + ?Sized
-        },
-    };
+    let key_bounds = borrowed_key_bounds(field_idents, field_type, ordering);
+    let lookup_key = wrap_key_ref(field_idents, quote! { key });
 
     match uniqueness {
         Uniqueness::Unique => quote! {
             #field_vis fn #getter_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> Option<&#element_name #types>
             where
-                #field_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
-                #key_bounds,
+                #key_bounds
             {
-                Some(&self._store[*self.#index_name.get(key)?])
+                Some(&self._store[*self.#index_name.get(#lookup_key)?])
             }
         },
         Uniqueness::NonUnique => quote! {
             #field_vis fn #getter_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> Vec<&#element_name #types>
             where
-                #field_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
-                #key_bounds,
+                #key_bounds
             {
-                if let Some(idxs) = self.#index_name.get(key) {
+                if let Some(idxs) = self.#index_name.get(#lookup_key) {
                     let mut elem_refs = Vec::with_capacity(idxs.len());
                     for idx in idxs {
                         elem_refs.push(&self._store[*idx])
@@ -416,30 +1067,117 @@ fn generate_field_getter(
     }
 }
 
-// TokenStream representing the get_mut_by_ accessor for this field.
-fn generate_field_mut_getter(
+// TokenStream representing the get_iter_by_ accessor for this field, only generated for non-unique indexes.
+// This lazily walks the set of positions stored under the key, rather than collecting references into a Vec.
+fn generate_field_iter_by_key_getter(
     field_idents: &FieldIdents,
     field_info: &FieldInfo,
+    ordering: &Ordering,
     uniqueness: &Uniqueness,
-    unindexed_types: &[&Type],
-    unindexed_idents: &[&Ident],
+    matches_name: &Ident,
+    iter_generics: &Generics,
 ) -> proc_macro2::TokenStream {
-    let mut_getter_name = format_ident!("get_mut_by_{}", &field_idents.name);
+    let Uniqueness::NonUnique = uniqueness else {
+        return quote! {};
+    };
+
+    let iter_getter_name = format_ident!("get_iter_by_{}", &field_idents.name);
     let index_name = &field_idents.index_name;
     let field_vis = &field_info.vis;
     let field_type = &field_info.ty;
-    let field_name_str = &field_info.str;
+    let (_, iter_types, _) = iter_generics.split_for_impl();
 
-    match uniqueness {
-        Uniqueness::Unique => quote! {
-            #field_vis fn #mut_getter_name(&mut self, key: &#field_type) -> Option<(#(&mut #unindexed_types,)*)> {
-                let elem = &mut self._store[*self.#index_name.get(key)?];
-                Some((#(&mut elem.#unindexed_idents,)*))
+    let key_bounds = borrowed_key_bounds(field_idents, field_type, ordering);
+    let lookup_key = wrap_key_ref(field_idents, quote! { key });
+
+    quote! {
+        #field_vis fn #iter_getter_name<'__mim_iter_lifetime, __MultiIndexMapKeyType>(
+            &'__mim_iter_lifetime self,
+            key: &__MultiIndexMapKeyType,
+        ) -> #matches_name #iter_types
+        where
+            #key_bounds
+        {
+            #matches_name {
+                _store_ref: &self._store,
+                _iter: self.#index_name.get(#lookup_key).map(|idxs| idxs.iter()),
+            }
+        }
+    }
+}
+
+// TokenStream representing the count_by_, contains_ and distinct_count_ accessors for this field.
+// These only read the lookup table, without visiting the backing storage.
+// count_by_ is only generated for non-unique indexes, as a unique index holds at most one element per key.
+fn generate_field_counters(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    ordering: &Ordering,
+    uniqueness: &Uniqueness,
+) -> proc_macro2::TokenStream {
+    let counter_name = format_ident!("count_by_{}", &field_idents.name);
+    let contains_name = format_ident!("contains_{}", &field_idents.name);
+    let distinct_counter_name = format_ident!("distinct_count_{}", &field_idents.name);
+    let index_name = &field_idents.index_name;
+    let field_vis = &field_info.vis;
+    let field_type = &field_info.ty;
+
+    let key_bounds = borrowed_key_bounds(field_idents, field_type, ordering);
+    let lookup_key = wrap_key_ref(field_idents, quote! { key });
+
+    let counter = match uniqueness {
+        Uniqueness::Unique => quote! {},
+        Uniqueness::NonUnique => quote! {
+            #field_vis fn #counter_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> usize
+            where
+                #key_bounds
+            {
+                self.#index_name.get(#lookup_key).map_or(0, |idxs| idxs.len())
+            }
+        },
+    };
+
+    quote! {
+        #counter
+
+        #field_vis fn #contains_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> bool
+        where
+            #key_bounds
+        {
+            self.#index_name.contains_key(#lookup_key)
+        }
+
+        #field_vis fn #distinct_counter_name(&self) -> usize {
+            self.#index_name.len()
+        }
+    }
+}
+
+// TokenStream representing the get_mut_by_ accessor for this field.
+fn generate_field_mut_getter(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    uniqueness: &Uniqueness,
+    unindexed_types: &[&Type],
+    unindexed_idents: &[&Ident],
+) -> proc_macro2::TokenStream {
+    let mut_getter_name = format_ident!("get_mut_by_{}", &field_idents.name);
+    let index_name = &field_idents.index_name;
+    let lookup_key = wrap_key_ref(field_idents, quote! { key });
+    let field_vis = &field_info.vis;
+    let field_type = &field_info.ty;
+    let field_name_str = &field_info.str;
+
+    match uniqueness {
+        Uniqueness::Unique => quote! {
+            #field_vis fn #mut_getter_name(&mut self, key: &#field_type) -> Option<(#(&mut #unindexed_types,)*)> {
+                let elem = &mut self._store[*self.#index_name.get(#lookup_key)?];
+                Some((#(&mut elem.#unindexed_idents,)*))
             }
         },
         Uniqueness::NonUnique => quote! {
             #field_vis fn #mut_getter_name(&mut self, key: &#field_type) -> Vec<(#(&mut #unindexed_types,)*)> {
-                if let Some(idxs) = self.#index_name.get(key) {
+                if let Some(idxs) = self.#index_name.get(#lookup_key) {
                     // Use a single iterator over the slab and advance it to each desired key,
                     // matching by slab index to safely build multiple &mut refs without UB.
                     // Avoids E0499 (multiple mutable borrows) that would arise from repeatedly
@@ -486,6 +1224,7 @@ fn generate_field_remover(
 ) -> proc_macro2::TokenStream {
     let remover_name = format_ident!("remove_by_{}", &field_idents.name);
     let index_name = &field_idents.index_name;
+    let lookup_key = wrap_key_ref(field_idents, quote! { key });
     let field_vis = &field_info.vis;
     let field_type = &field_info.ty;
     let (_, types, _) = generics.split_for_impl();
@@ -493,7 +1232,7 @@ fn generate_field_remover(
     match uniqueness {
         Uniqueness::Unique => quote! {
             #field_vis fn #remover_name(&mut self, key: &#field_type) -> Option<#element_name #types> {
-                let idx = self.#index_name.remove(key)?;
+                let idx = self.#index_name.remove(#lookup_key)?;
                 let elem_orig = self._store.remove(idx);
                 #(#removes)*
                 Some(elem_orig)
@@ -501,7 +1240,7 @@ fn generate_field_remover(
         },
         Uniqueness::NonUnique => quote! {
             #field_vis fn #remover_name(&mut self, key: &#field_type) -> Vec<#element_name #types> {
-                if let Some(idxs) = self.#index_name.remove(key) {
+                if let Some(idxs) = self.#index_name.remove(#lookup_key) {
                     let mut elems = Vec::with_capacity(idxs.len());
                     for idx in idxs {
                         let elem_orig = self._store.remove(idx);
@@ -535,14 +1274,8 @@ fn generate_field_updater(
     let field_name_str = &field_info.str;
     let (_, element_types, _) = generics.split_for_impl();
 
-    let key_bounds = match ordering {
-        Ordering::Hashed => quote! {
-            __MultiIndexMapKeyType: ::std::hash::Hash + Eq + ?Sized
-        },
-        Ordering::Ordered => quote! {
-            __MultiIndexMapKeyType: Ord + ?Sized
-        },
-    };
+    let key_bounds = borrowed_key_bounds(field_idents, field_type, ordering);
+    let lookup_key = wrap_key_ref(field_idents, quote! { key });
 
     match uniqueness {
         Uniqueness::Unique => quote! {
@@ -552,10 +1285,9 @@ fn generate_field_updater(
                 f: impl FnOnce(#(&mut #unindexed_types,)*)
             ) -> Option<&#element_name #element_types>
             where
-                #field_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
-                #key_bounds,
+                #key_bounds
             {
-                let idx = *self.#index_name.get(key)?;
+                let idx = *self.#index_name.get(#lookup_key)?;
                 let elem = &mut self._store[idx];
                 f(#(&mut elem.#unindexed_idents,)*);
                 Some(elem)
@@ -568,10 +1300,9 @@ fn generate_field_updater(
                 mut f: impl FnMut(#(&mut #unindexed_types,)*)
             ) -> Vec<&#element_name #element_types>
             where
-                #field_type: ::std::borrow::Borrow<__MultiIndexMapKeyType>,
-                #key_bounds,
+                #key_bounds
             {
-                let idxs_ref = match self.#index_name.get(key) {
+                let idxs_ref = match self.#index_name.get(#lookup_key) {
                     Some(container) => container,
                     None => return Vec::new(),
                 };
@@ -622,6 +1353,7 @@ fn generate_field_modifier(
 ) -> proc_macro2::TokenStream {
     let modifier_name = format_ident!("modify_by_{}", &field_idents.name);
     let index_name = &field_idents.index_name;
+    let lookup_key = wrap_key_ref(field_idents, quote! { key });
     let field_vis = &field_info.vis;
     let field_type = &field_info.ty;
     let field_name_str = &field_info.str;
@@ -634,7 +1366,7 @@ fn generate_field_modifier(
                 key: &#field_type,
                 f: impl FnOnce(&mut #element_name #types)
             ) -> Option<&#element_name #types> {
-                let idx = *self.#index_name.get(key)?;
+                let idx = *self.#index_name.get(#lookup_key)?;
                 let elem = &mut self._store[idx];
                 #(#pre_modifies)*
                 f(elem);
@@ -648,7 +1380,7 @@ fn generate_field_modifier(
                 key: &#field_type,
                 mut f: impl FnMut(&mut #element_name #types)
             ) -> Vec<&#element_name #types> {
-                let idxs_set = match self.#index_name.get(key) {
+                let idxs_set = match self.#index_name.get(#lookup_key) {
                     Some(container) => container.clone(), // clone to decouple from mutations during post_modifies
                     None => return Vec::new(),
                 };
@@ -685,6 +1417,217 @@ fn generate_field_modifier(
     }
 }
 
+// TokenStream representing the try_modify_by_ accessor for this field.
+// Like modify_by_, but if the modification would violate any uniqueness constraint, the element is restored
+//   from a clone taken before the modification and an error naming the violated index is returned, rather than panicking.
+// The uniqueness checks are all made before any lookup table is changed, so nothing else needs undoing.
+// For non-unique indexes, each element is modified in turn, and if any element fails then the elements
+//   already modified are restored in reverse order, along with their lookup table entries.
+//   Any keys they vacated can only have been taken by elements modified after them, which are restored first.
+// Only available when the element implements Clone. The bound is higher-ranked so that it is not a trivial
+//   bound for non-generic elements, which would otherwise fail to compile when the element is not Clone.
+#[allow(clippy::too_many_arguments)]
+fn generate_field_try_modifier(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    element_name: &Ident,
+    uniqueness: &Uniqueness,
+    pre_modifies: &[proc_macro2::TokenStream],
+    post_modifies: &[proc_macro2::TokenStream],
+    uniqueness_checks: &[proc_macro2::TokenStream],
+    key_name: &Ident,
+    existing_handle: &proc_macro2::TokenStream,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let try_modifier_name = format_ident!("try_modify_by_{}", &field_idents.name);
+    let index_name = &field_idents.index_name;
+    let lookup_key = wrap_key_ref(field_idents, quote! { key });
+    let field_vis = &field_info.vis;
+    let field_type = &field_info.ty;
+    let field_name_str = &field_info.str;
+    let (_, types, _) = generics.split_for_impl();
+
+    match uniqueness {
+        Uniqueness::Unique => quote! {
+            /// Modify the element with the given key, as with `modify_by_`.
+            /// If the modification would violate a uniqueness constraint, the element is restored to its
+            ///   previous state and an error naming the violated index is returned.
+            #field_vis fn #try_modifier_name(
+                &mut self,
+                key: &#field_type,
+                f: impl FnOnce(&mut #element_name #types)
+            ) -> Result<Option<&#element_name #types>, ::multi_index_map::ModifyError<#key_name>>
+            where
+                for<'__mim_clone_lifetime> #element_name #types: Clone,
+            {
+                let Some(&idx) = self.#index_name.get(#lookup_key) else {
+                    return Ok(None);
+                };
+                let elem = &mut self._store[idx];
+                let elem_orig = elem.clone();
+                #(#pre_modifies)*
+                f(elem);
+                let violation: Option<(&'static str, usize)> = #(#uniqueness_checks)* { None };
+                if let Some((index, existing)) = violation {
+                    *elem = elem_orig;
+                    return Err(::multi_index_map::ModifyError { index, existing: #existing_handle });
+                }
+                #(#post_modifies)*
+                Ok(Some(elem))
+            }
+        },
+        Uniqueness::NonUnique => quote! {
+            /// Modify every element with the given key, as with `modify_by_`.
+            /// If any modification would violate a uniqueness constraint, every element modified by this call
+            ///   is restored to its previous state and an error naming the violated index is returned.
+            #field_vis fn #try_modifier_name(
+                &mut self,
+                key: &#field_type,
+                mut f: impl FnMut(&mut #element_name #types)
+            ) -> Result<Vec<&#element_name #types>, ::multi_index_map::ModifyError<#key_name>>
+            where
+                for<'__mim_clone_lifetime> #element_name #types: Clone,
+            {
+                let targets: ::std::vec::Vec<usize> = match self.#index_name.get(#lookup_key) {
+                    Some(container) => container.iter().copied().collect(),
+                    None => return Ok(Vec::new()),
+                };
+
+                let mut modified: ::std::vec::Vec<(usize, #element_name #types)> =
+                    ::std::vec::Vec::with_capacity(targets.len());
+                for &idx in &targets {
+                    let Some(elem) = self._store.get_mut(idx) else {
+                        panic!(
+                            "Internal invariants broken, unable to find element at index {} in store despite being present in index '{}'",
+                            idx,
+                            #field_name_str
+                        );
+                    };
+                    let elem_orig = elem.clone();
+                    #(#pre_modifies)*
+                    f(elem);
+                    let violation: Option<(&'static str, usize)> = #(#uniqueness_checks)* { None };
+                    if let Some((index, existing)) = violation {
+                        *elem = elem_orig;
+                        while let Some((idx, elem_orig)) = modified.pop() {
+                            let elem = &mut self._store[idx];
+                            #(#pre_modifies)*
+                            *elem = elem_orig;
+                            #(#post_modifies)*
+                        }
+                        return Err(::multi_index_map::ModifyError { index, existing: #existing_handle });
+                    }
+                    #(#post_modifies)*
+                    modified.push((idx, elem_orig));
+                }
+
+                let mut refs = ::std::vec::Vec::with_capacity(targets.len());
+                for &idx in &targets {
+                    refs.push(&self._store[idx]);
+                }
+                Ok(refs)
+            }
+        },
+    }
+}
+
+// TokenStream representing the upsert_by_ accessor for this field, only generated for unique indexes.
+// Any element with the same key in this index is replaced by the new element and returned.
+// If the new element would collide with any other element in another unique index,
+//   the map is left unchanged and the new element is returned in a UniquenessError.
+#[allow(clippy::too_many_arguments)]
+fn generate_field_upserter(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    element_name: &Ident,
+    uniqueness: &Uniqueness,
+    removes: &[proc_macro2::TokenStream],
+    unique_lookups: &[proc_macro2::TokenStream],
+    key_name: &Ident,
+    existing_handle: &proc_macro2::TokenStream,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let Uniqueness::Unique = uniqueness else {
+        return quote! {};
+    };
+
+    let upserter_name = format_ident!("upsert_by_{}", &field_idents.name);
+    let field_vis = &field_info.vis;
+    let (_, types, _) = generics.split_for_impl();
+    let lookup = unique_lookup(field_idents, &format_ident!("elem"));
+
+    quote! {
+        #field_vis fn #upserter_name(
+            &mut self,
+            elem: #element_name #types
+        ) -> Result<Option<#element_name #types>, ::multi_index_map::UniquenessError<#element_name #types, #key_name>> {
+            let replaced_idx = #lookup;
+            for (index, existing) in [#(#unique_lookups,)*] {
+                match existing {
+                    Some(existing) if Some(existing) != replaced_idx => {
+                        return Err(::multi_index_map::UniquenessError {
+                            elem,
+                            index,
+                            existing: #existing_handle,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+
+            let replaced = if let Some(idx) = replaced_idx {
+                let elem_orig = self._store.remove(idx);
+                #(#removes)*
+                Some(elem_orig)
+            } else {
+                None
+            };
+            self.insert(elem);
+            Ok(replaced)
+        }
+    }
+}
+
+// TokenStream representing the entry_by_ accessor for this field, only generated for unique indexes.
+// The key is looked up once to find the position of the matching element.
+// When it is vacant, the key is moved into an entry of the lookup table, which a newly inserted element fills.
+fn generate_field_entry_getter(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    uniqueness: &Uniqueness,
+    occupied_entry_name: &Ident,
+    existing_handle: &proc_macro2::TokenStream,
+    iter_generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let Uniqueness::Unique = uniqueness else {
+        return quote! {};
+    };
+
+    let entry_getter_name = format_ident!("entry_by_{}", &field_idents.name);
+    let entry_name = &field_idents.entry_name;
+    let vacant_entry_name = &field_idents.vacant_entry_name;
+    let index_name = &field_idents.index_name;
+    let lookup_key = wrap_key_ref(field_idents, quote! { &key });
+    let field_vis = &field_info.vis;
+    let field_type = &field_info.ty;
+    let (_, iter_types, _) = iter_generics.split_for_impl();
+
+    quote! {
+        #field_vis fn #entry_getter_name<'__mim_iter_lifetime>(
+            &'__mim_iter_lifetime mut self,
+            key: #field_type,
+        ) -> #entry_name #iter_types {
+            match self.#index_name.get(#lookup_key) {
+                Some(&existing) => {
+                    let key = #existing_handle;
+                    #entry_name::Occupied(#occupied_entry_name { _map: self, _key: key })
+                }
+                None => #entry_name::Vacant(#vacant_entry_name::_new(self, key)),
+            }
+        }
+    }
+}
+
 fn generate_field_iter_getter(
     field_idents: &FieldIdents,
     field_info: &FieldInfo,
@@ -722,42 +1665,931 @@ fn generate_field_iter_getter(
     }
 }
 
-pub(crate) fn generate_iter_mut(
-    iter_mut_name: &proc_macro2::Ident,
-    element_name: &proc_macro2::Ident,
-    element_vis: &Visibility,
-    unindexed_types: &[&Type],
-    unindexed_idents: &[&Ident],
+// TokenStream representing the first_by_, last_by_, pop_first_by_ and pop_last_by_ accessors for this field.
+// Only ordered indexes have a first and last key, as these are backed by a BTreeMap.
+// For non-unique indexes, the first or last key may match several elements,
+//   in which case the element earliest or latest in the backing storage is chosen, as in iter_by_.
+fn generate_field_first_last_getters(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    element_name: &Ident,
+    ordering: &Ordering,
+    uniqueness: &Uniqueness,
     generics: &Generics,
-    iter_generics: &Generics,
 ) -> proc_macro2::TokenStream {
+    let Ordering::Ordered = ordering else {
+        return quote! {};
+    };
+
+    let first_getter_name = format_ident!("first_by_{}", &field_idents.name);
+    let last_getter_name = format_ident!("last_by_{}", &field_idents.name);
+    let pop_first_name = format_ident!("pop_first_by_{}", &field_idents.name);
+    let pop_last_name = format_ident!("pop_last_by_{}", &field_idents.name);
+    let index_name = &field_idents.index_name;
+    let field_vis = &field_info.vis;
     let (_, types, _) = generics.split_for_impl();
-    let (iter_impls, iter_types, iter_where_clause) = iter_generics.split_for_impl();
+    let error_msg = format!(
+        "Internal invariants broken, found empty slice in non_unique index '{}'",
+        field_info.str
+    );
+
+    let (first_idx, last_idx) = match uniqueness {
+        Uniqueness::Unique => (
+            quote! { *self.#index_name.iter().next()?.1 },
+            quote! { *self.#index_name.iter().next_back()?.1 },
+        ),
+        Uniqueness::NonUnique => (
+            quote! { *self.#index_name.iter().next()?.1.iter().next().expect(#error_msg) },
+            quote! { *self.#index_name.iter().next_back()?.1.iter().next_back().expect(#error_msg) },
+        ),
+    };
 
     quote! {
-        #element_vis struct #iter_mut_name #iter_impls (::multi_index_map::slab::IterMut<'__mim_iter_lifetime, #element_name #types>);
+        #field_vis fn #first_getter_name(&self) -> Option<&#element_name #types> {
+            let idx = #first_idx;
+            Some(&self._store[idx])
+        }
 
-        impl #iter_impls Iterator for #iter_mut_name #iter_types #iter_where_clause {
-            type Item = (#(&'__mim_iter_lifetime mut #unindexed_types,)*);
+        #field_vis fn #last_getter_name(&self) -> Option<&#element_name #types> {
+            let idx = #last_idx;
+            Some(&self._store[idx])
+        }
 
-            fn next(&mut self) -> Option<Self::Item> {
-                self.0.next().map(|(_, elem)| (#(&mut elem.#unindexed_idents,)*))
+        #field_vis fn #pop_first_name(&mut self) -> Option<#element_name #types> {
+            let idx = #first_idx;
+            Some(self._remove_at(idx))
+        }
+
+        #field_vis fn #pop_last_name(&mut self) -> Option<#element_name #types> {
+            let idx = #last_idx;
+            Some(self._remove_at(idx))
+        }
+    }
+}
+
+// TokenStream representing the keys_by_ and groups_by_ accessors for this field.
+// Both walk the lookup table directly, so each distinct key is visited once, in index order for ordered indexes.
+fn generate_field_keys_getter(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    ordering: &Ordering,
+    uniqueness: &Uniqueness,
+    iter_generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let keys_getter_name = format_ident!("keys_by_{}", &field_idents.name);
+    let groups_getter_name = format_ident!("groups_by_{}", &field_idents.name);
+    let groups_name = &field_idents.groups_name;
+    let index_name = &field_idents.index_name;
+    let field_vis = &field_info.vis;
+    let field_type = &field_info.ty;
+    let (_, iter_types, _) = iter_generics.split_for_impl();
+
+    let lookup_ty = lookup_key_type(field_idents, field_type);
+    let keys_type = lookup_table_iter_type(&lookup_ty, ordering, uniqueness, &format_ident!("Keys"));
+    // The keys of a lookup table with a key wrapper are unwrapped through a function pointer,
+    //   so that the type of the iterator can still be named.
+    let (keys_type, keys) = match &field_idents.key_wrapper {
+        Some(_) => (
+            quote! {
+                ::std::iter::Map<
+                    #keys_type,
+                    fn(&'__mim_iter_lifetime #lookup_ty) -> &'__mim_iter_lifetime #field_type,
+                >
+            },
+            quote! {
+                self.#index_name.keys().map(<#lookup_ty>::as_key as fn(&'__mim_iter_lifetime #lookup_ty) -> &'__mim_iter_lifetime #field_type)
+            },
+        ),
+        None => (keys_type, quote! { self.#index_name.keys() }),
+    };
+
+    quote! {
+        #field_vis fn #keys_getter_name<'__mim_iter_lifetime>(&'__mim_iter_lifetime self) -> #keys_type {
+            #keys
+        }
+
+        #field_vis fn #groups_getter_name<'__mim_iter_lifetime>(&'__mim_iter_lifetime self) -> #groups_name #iter_types {
+            #groups_name {
+                _store_ref: &self._store,
+                _iter: self.#index_name.iter(),
             }
         }
+    }
+}
 
-        impl #iter_impls DoubleEndedIterator for #iter_mut_name #iter_types #iter_where_clause {
-            fn next_back(&mut self) -> Option<Self::Item> {
-                self.0.next_back().map(|(_, elem)| (#(&mut elem.#unindexed_idents,)*))
+// TokenStream representing the type of an iterator over the lookup table of an index, such as Iter or Keys,
+//   borrowing the lookup table for '__mim_iter_lifetime.
+fn lookup_table_iter_type(
+    ty: &::proc_macro2::TokenStream,
+    ordering: &Ordering,
+    uniqueness: &Uniqueness,
+    iter_type_name: &Ident,
+) -> proc_macro2::TokenStream {
+    let value_type = match uniqueness {
+        Uniqueness::Unique => quote! { usize },
+        Uniqueness::NonUnique => quote! { ::std::collections::BTreeSet<usize> },
+    };
+    match ordering {
+        Ordering::Hashed => quote! {
+            ::std::collections::hash_map::#iter_type_name<'__mim_iter_lifetime, #ty, #value_type>
+        },
+        Ordering::Ordered => quote! {
+            ::std::collections::btree_map::#iter_type_name<'__mim_iter_lifetime, #ty, #value_type>
+        },
+    }
+}
+
+// TokenStream representing the range_by_ accessor for this field.
+// Only ordered indexes can be queried by range, as these are backed by a BTreeMap.
+// As with get_by_, the bounds can be given as any borrowed form of the key type.
+fn generate_field_range_getter(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    ordering: &Ordering,
+    uniqueness: &Uniqueness,
+    iter_generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let Ordering::Ordered = ordering else {
+        return quote! {};
+    };
+
+    let range_getter_name = format_ident!("range_by_{}", &field_idents.name);
+    let range_iter_name = &field_idents.range_iter_name;
+    let index_name = &field_idents.index_name;
+    let field_vis = &field_info.vis;
+    let key_bounds = borrowed_key_bounds(field_idents, field_info.ty, ordering);
+    let range = wrap_range(field_idents, quote! { range });
+    let (_, iter_types, _) = iter_generics.split_for_impl();
+
+    let iterator_def = match uniqueness {
+        Uniqueness::Unique => quote! {
+            #range_iter_name {
+                _store_ref: &self._store,
+                _range: self.#index_name.range(#range),
+            }
+        },
+        Uniqueness::NonUnique => quote! {
+            #range_iter_name {
+                _store_ref: &self._store,
+                _range: self.#index_name.range(#range),
+                _front_iter: None,
+                _back_iter: None,
+            }
+        },
+    };
+
+    quote! {
+        #field_vis fn #range_getter_name<'__mim_iter_lifetime, __MultiIndexMapKeyType, __MultiIndexMapRangeType>(
+            &'__mim_iter_lifetime self,
+            range: __MultiIndexMapRangeType,
+        ) -> #range_iter_name #iter_types
+        where
+            #key_bounds
+            __MultiIndexMapRangeType: ::std::ops::RangeBounds<__MultiIndexMapKeyType>,
+        {
+            #iterator_def
+        }
+    }
+}
+
+// TokenStream representing the rank_by_, nth_by_ and count_range_by_ accessors for this field.
+// Only `ordered_ranked` indexes have these, as they are answered by the order-statistic tree kept alongside the
+//   lookup table, in O(log n) rather than by walking the BTreeMap.
+// Elements with equal keys share a rank, and are ordered by their position in the backing storage within nth_by_.
+fn generate_field_rank_getters(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    element_name: &Ident,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let Some(ranks_name) = &field_idents.ranks_name else {
+        return quote! {};
+    };
+
+    let rank_getter_name = format_ident!("rank_by_{}", &field_idents.name);
+    let nth_getter_name = format_ident!("nth_by_{}", &field_idents.name);
+    let count_range_name = format_ident!("count_range_by_{}", &field_idents.name);
+    let field_vis = &field_info.vis;
+    let key_bounds = borrowed_key_bounds(field_idents, field_info.ty, &Ordering::Ordered);
+    let lookup_key = wrap_key_ref(field_idents, quote! { key });
+    let range = wrap_range(field_idents, quote! { range });
+    let (_, types, _) = generics.split_for_impl();
+
+    quote! {
+        #field_vis fn #rank_getter_name<__MultiIndexMapKeyType>(&self, key: &__MultiIndexMapKeyType) -> usize
+        where
+            #key_bounds
+        {
+            self.#ranks_name.rank(#lookup_key)
+        }
+
+        #field_vis fn #nth_getter_name(&self, n: usize) -> Option<&#element_name #types> {
+            let idx = self.#ranks_name.nth(n)?;
+            Some(&self._store[idx])
+        }
+
+        #field_vis fn #count_range_name<__MultiIndexMapKeyType, __MultiIndexMapRangeType>(
+            &self,
+            range: __MultiIndexMapRangeType,
+        ) -> usize
+        where
+            #key_bounds
+            __MultiIndexMapRangeType: ::std::ops::RangeBounds<__MultiIndexMapKeyType>,
+        {
+            self.#ranks_name.count_range(#range)
+        }
+    }
+}
+
+// TokenStream representing the positions in the backing storage of the elements in `range` of this ordered index,
+//   in the order of the index, used by the accessors which remove or modify a range of elements.
+// An element of a multi-valued index is in the lookup table under each of its items, so each position is only
+//   kept the first time it is seen, otherwise the element would be removed or modified more than once.
+fn range_targets(
+    field_idents: &FieldIdents,
+    uniqueness: &Uniqueness,
+    range: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let index_name = &field_idents.index_name;
+    match (uniqueness, &field_idents.multi) {
+        (Uniqueness::Unique, _) => quote! {
+            self.#index_name.range(#range).map(|(_, idx)| *idx).collect::<::std::vec::Vec<usize>>()
+        },
+        (Uniqueness::NonUnique, None) => quote! {
+            self.#index_name
+                .range(#range)
+                .flat_map(|(_, idxs)| idxs.iter().copied())
+                .collect::<::std::vec::Vec<usize>>()
+        },
+        (Uniqueness::NonUnique, Some(_)) => quote! {
+            {
+                let mut seen = ::std::collections::BTreeSet::new();
+                self.#index_name
+                    .range(#range)
+                    .flat_map(|(_, idxs)| idxs.iter().copied())
+                    .filter(|idx| seen.insert(*idx))
+                    .collect::<::std::vec::Vec<usize>>()
+            }
+        },
+    }
+}
+
+// TokenStream representing the remove_range_by_ and modify_range_by_ accessors for this field.
+// Only ordered indexes can be queried by range, as these are backed by a BTreeMap.
+// The positions of all elements in the range are collected before any element is removed or modified,
+//   so that each is visited exactly once, even if the modify closure moves elements into or out of the range.
+// Each element is then removed or modified through its position, rather than inlining removes or
+//   pre_modifies and post_modifies, as the Borrow bound on the key type would interfere with their type inference.
+fn generate_field_range_modifiers(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    element_name: &Ident,
+    ordering: &Ordering,
+    uniqueness: &Uniqueness,
+    existing_handle: &proc_macro2::TokenStream,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let Ordering::Ordered = ordering else {
+        return quote! {};
+    };
+
+    let range_remover_name = format_ident!("remove_range_by_{}", &field_idents.name);
+    let range_modifier_name = format_ident!("modify_range_by_{}", &field_idents.name);
+    let field_vis = &field_info.vis;
+    let key_bounds = borrowed_key_bounds(field_idents, field_info.ty, ordering);
+    let range = wrap_range(field_idents, quote! { range });
+    let (_, types, _) = generics.split_for_impl();
+
+    let targets = range_targets(field_idents, uniqueness, &range);
+
+    quote! {
+        #field_vis fn #range_remover_name<__MultiIndexMapKeyType, __MultiIndexMapRangeType>(
+            &mut self,
+            range: __MultiIndexMapRangeType,
+        ) -> Vec<#element_name #types>
+        where
+            #key_bounds
+            __MultiIndexMapRangeType: ::std::ops::RangeBounds<__MultiIndexMapKeyType>,
+        {
+            let targets = #targets;
+            targets.into_iter().map(|idx| self._remove_at(idx)).collect()
+        }
+
+        #field_vis fn #range_modifier_name<__MultiIndexMapKeyType, __MultiIndexMapRangeType>(
+            &mut self,
+            range: __MultiIndexMapRangeType,
+            mut f: impl FnMut(&mut #element_name #types),
+        ) -> Vec<&#element_name #types>
+        where
+            #key_bounds
+            __MultiIndexMapRangeType: ::std::ops::RangeBounds<__MultiIndexMapKeyType>,
+        {
+            let targets = #targets;
+
+            for &existing in &targets {
+                self.modify(#existing_handle, &mut f);
+            }
+
+            let mut refs = ::std::vec::Vec::with_capacity(targets.len());
+            for &idx in &targets {
+                refs.push(&self._store[idx]);
+            }
+            refs
+        }
+    }
+}
+
+// TokenStream representing the split_off_by_ accessor for this field, mirroring BTreeMap::split_off.
+// Only ordered indexes can be split, as these are backed by a BTreeMap.
+// Each element at or after the key is removed, then inserted into a new map, which builds its own lookup tables.
+// The elements are inserted in the order of the map rather than of the index, so that a sequenced or random_access
+//   index of the new map keeps their relative order.
+fn generate_field_splitter(
+    field_idents: &FieldIdents,
+    field_info: &FieldInfo,
+    ordering: &Ordering,
+    uniqueness: &Uniqueness,
+) -> proc_macro2::TokenStream {
+    let Ordering::Ordered = ordering else {
+        return quote! {};
+    };
+
+    let splitter_name = format_ident!("split_off_by_{}", &field_idents.name);
+    let field_vis = &field_info.vis;
+    let key_bounds = borrowed_key_bounds(field_idents, field_info.ty, ordering);
+    let range = wrap_range(field_idents, quote! { range });
+    // The split is in the order of the index, so for a descending order the range of keys in ascending order
+    //   ends at the given key.
+    let split_range = match &field_idents.key_wrapper {
+        Some(wrapper) if wrapper.reversed => quote! { (::std::ops::Bound::Unbounded, ::std::ops::Bound::Included(key)) },
+        _ => quote! { (::std::ops::Bound::Included(key), ::std::ops::Bound::Unbounded) },
+    };
+
+    let targets = range_targets(field_idents, uniqueness, &range);
+
+    quote! {
+        /// Move the element with the given key and every element after it in the order of the index into a new map.
+        /// The moved elements keep their relative order in the map, eg. in a sequenced index.
+        /// Key handles to the moved elements are not valid in the new map.
+        #field_vis fn #splitter_name<__MultiIndexMapKeyType>(&mut self, key: &__MultiIndexMapKeyType) -> Self
+        where
+            #key_bounds
+        {
+            let range = #split_range;
+            let targets = self._in_map_order(#targets);
+
+            let mut other = Self::with_capacity(targets.len());
+            for idx in targets {
+                other.insert(self._remove_at(idx));
+            }
+            other
+        }
+    }
+}
+
+pub(crate) fn generate_iter_mut(
+    iter_mut_name: &proc_macro2::Ident,
+    element_name: &proc_macro2::Ident,
+    element_vis: &Visibility,
+    unindexed_types: &[&Type],
+    unindexed_idents: &[&Ident],
+    generics: &Generics,
+    iter_generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+    let (iter_impls, iter_types, iter_where_clause) = iter_generics.split_for_impl();
+
+    quote! {
+        #element_vis struct #iter_mut_name #iter_impls (::multi_index_map::slab::IterMut<'__mim_iter_lifetime, #element_name #types>);
+
+        impl #iter_impls Iterator for #iter_mut_name #iter_types #iter_where_clause {
+            type Item = (#(&'__mim_iter_lifetime mut #unindexed_types,)*);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.0.next().map(|(_, elem)| (#(&mut elem.#unindexed_idents,)*))
+            }
+        }
+
+        impl #iter_impls DoubleEndedIterator for #iter_mut_name #iter_types #iter_where_clause {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.0.next_back().map(|(_, elem)| (#(&mut elem.#unindexed_idents,)*))
+            }
+        }
+
+        impl #iter_impls ExactSizeIterator for #iter_mut_name #iter_types #iter_where_clause {
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+        }
+
+        impl #iter_impls std::iter::FusedIterator for #iter_mut_name #iter_types #iter_where_clause {
+        }
+    }
+}
+
+// TokenStream representing the iterator returned by the get_iter_by_ accessors of non-unique indexes.
+// This is shared by all non-unique indexes, as each walks a set of positions in the backing storage.
+// It is named without an Iter suffix, so it can never collide with the iterator of a field.
+// A missing key is represented by having no set to walk, so the iterator is immediately exhausted.
+pub(crate) fn generate_matches(
+    matches_name: &proc_macro2::Ident,
+    element_name: &proc_macro2::Ident,
+    element_vis: &Visibility,
+    generics: &Generics,
+    iter_generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+    let (iter_impls, iter_types, iter_where_clause) = iter_generics.split_for_impl();
+
+    quote! {
+        #element_vis struct #matches_name #iter_impls #iter_where_clause {
+            _store_ref: &'__mim_iter_lifetime ::multi_index_map::slab::Slab<#element_name #types>,
+            _iter: Option<::std::collections::btree_set::Iter<'__mim_iter_lifetime, usize>>,
+        }
+
+        impl #iter_impls Iterator for #matches_name #iter_types #iter_where_clause {
+            type Item = &'__mim_iter_lifetime #element_name #types;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                let idx = self._iter.as_mut()?.next()?;
+                Some(&self._store_ref[*idx])
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let len = self.len();
+                (len, Some(len))
+            }
+        }
+
+        impl #iter_impls DoubleEndedIterator for #matches_name #iter_types #iter_where_clause {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                let idx = self._iter.as_mut()?.next_back()?;
+                Some(&self._store_ref[*idx])
+            }
+        }
+
+        impl #iter_impls ExactSizeIterator for #matches_name #iter_types #iter_where_clause {
+            fn len(&self) -> usize {
+                self._iter.as_ref().map_or(0, |iter| iter.len())
+            }
+        }
+
+        impl #iter_impls std::iter::FusedIterator for #matches_name #iter_types #iter_where_clause {
+        }
+    }
+}
+
+// TokenStream representing the accessors of the sequence of a sequenced map.
+// Every inserted element is linked at the back of the sequence, and unlinked as part of removes,
+//   so these only need to move elements within the sequence, or find the position of the element to remove.
+pub(crate) fn generate_sequenced_accessors(
+    extra_attrs: &ExtraAttributes,
+    sequenced_name: &Ident,
+    key_name: &Ident,
+    element_name: &Ident,
+    element_vis: &Visibility,
+    generics: &Generics,
+    iter_generics: &Generics,
+) -> proc_macro2::TokenStream {
+    if !extra_attrs.sequenced {
+        return quote! {};
+    }
+
+    let (_, types, _) = generics.split_for_impl();
+    let (_, iter_types, _) = iter_generics.split_for_impl();
+
+    quote! {
+        /// Insert an element at the back of the sequence, equivalent to try_insert.
+        #element_vis fn push_back(&mut self, elem: #element_name #types) -> Result<#key_name, ::multi_index_map::UniquenessError<#element_name #types, #key_name>> {
+            self.try_insert(elem)
+        }
+
+        /// Insert an element at the front of the sequence.
+        #element_vis fn push_front(&mut self, elem: #element_name #types) -> Result<#key_name, ::multi_index_map::UniquenessError<#element_name #types, #key_name>> {
+            let key = self.try_insert(elem)?;
+            self._sequence.relocate(key.0, self._sequence.front());
+            Ok(key)
+        }
+
+        /// Remove the element at the front of the sequence.
+        #element_vis fn pop_front(&mut self) -> Option<#element_name #types> {
+            let idx = self._sequence.front()?;
+            Some(self._remove_at(idx))
+        }
+
+        /// Remove the element at the back of the sequence.
+        #element_vis fn pop_back(&mut self) -> Option<#element_name #types> {
+            let idx = self._sequence.back()?;
+            Some(self._remove_at(idx))
+        }
+
+        /// Move the element at the given key to just before the element at `position` in the sequence,
+        ///   or to the back of the sequence if `position` is None.
+        /// Returns false, without moving any element, if either key no longer refers to an element in the map.
+        #element_vis fn relocate(&mut self, key: #key_name, position: Option<#key_name>) -> bool {
+            if self.get(key).is_none() || matches!(position, Some(position) if self.get(position).is_none()) {
+                return false;
+            }
+            self._sequence.relocate(key.0, position.map(|position| position.0));
+            true
+        }
+
+        #element_vis fn iter_sequenced<'__mim_iter_lifetime>(&'__mim_iter_lifetime self) -> #sequenced_name #iter_types {
+            #sequenced_name {
+                _store_ref: &self._store,
+                _iter: self._sequence.iter(),
+            }
+        }
+    }
+}
+
+// TokenStream representing the iterator over the elements of a sequenced map, in sequence order.
+// This is named without an Iter suffix, so it can never collide with the iterator of a field.
+pub(crate) fn generate_sequenced_iter(
+    extra_attrs: &ExtraAttributes,
+    sequenced_name: &proc_macro2::Ident,
+    element_name: &proc_macro2::Ident,
+    element_vis: &Visibility,
+    generics: &Generics,
+    iter_generics: &Generics,
+) -> proc_macro2::TokenStream {
+    if !extra_attrs.sequenced {
+        return quote! {};
+    }
+
+    let (_, types, _) = generics.split_for_impl();
+    let (iter_impls, iter_types, iter_where_clause) = iter_generics.split_for_impl();
+
+    quote! {
+        #element_vis struct #sequenced_name #iter_impls #iter_where_clause {
+            _store_ref: &'__mim_iter_lifetime ::multi_index_map::slab::Slab<#element_name #types>,
+            _iter: ::multi_index_map::sequence::Iter<'__mim_iter_lifetime>,
+        }
+
+        impl #iter_impls Iterator for #sequenced_name #iter_types #iter_where_clause {
+            type Item = &'__mim_iter_lifetime #element_name #types;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                Some(&self._store_ref[self._iter.next()?])
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self._iter.size_hint()
+            }
+        }
+
+        impl #iter_impls DoubleEndedIterator for #sequenced_name #iter_types #iter_where_clause {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                Some(&self._store_ref[self._iter.next_back()?])
+            }
+        }
+
+        impl #iter_impls ExactSizeIterator for #sequenced_name #iter_types #iter_where_clause {
+            fn len(&self) -> usize {
+                self._iter.len()
+            }
+        }
+
+        impl #iter_impls std::iter::FusedIterator for #sequenced_name #iter_types #iter_where_clause {
+        }
+    }
+}
+
+// TokenStream representing the accessors by position of a map with a random_access index.
+// Every inserted element is appended to the dense sequence of positions, and removed from it as part of removes,
+//   so these only need to move elements within the sequence, or find the position of the element to remove.
+pub(crate) fn generate_random_access_accessors(
+    extra_attrs: &ExtraAttributes,
+    random_access_name: &Ident,
+    key_name: &Ident,
+    element_name: &Ident,
+    element_vis: &Visibility,
+    generics: &Generics,
+    iter_generics: &Generics,
+) -> proc_macro2::TokenStream {
+    if !extra_attrs.random_access {
+        return quote! {};
+    }
+
+    let (_, types, _) = generics.split_for_impl();
+    let (_, iter_types, _) = iter_generics.split_for_impl();
+
+    quote! {
+        /// Get the element at offset `n` in the random access sequence.
+        #element_vis fn nth(&self, n: usize) -> Option<&#element_name #types> {
+            Some(&self._store[self._random_access.nth(n)?])
+        }
+
+        /// Get the offset in the random access sequence of the element at the given key.
+        #element_vis fn position_of(&self, key: #key_name) -> Option<usize> {
+            self.get(key)?;
+            Some(self._random_access.offset_of(key.0))
+        }
+
+        /// Insert an element at offset `n` in the random access sequence, shifting all later elements.
+        /// Panics if `n` is greater than the number of elements, before the element is inserted.
+        #element_vis fn insert_at(&mut self, n: usize, elem: #element_name #types) -> Result<#key_name, ::multi_index_map::UniquenessError<#element_name #types, #key_name>> {
+            if n > self._random_access.len() {
+                panic!(
+                    "Unable to insert element, offset {} is out of bounds for {} elements",
+                    n,
+                    self._random_access.len()
+                );
+            }
+            let key = self.try_insert(elem)?;
+            self._random_access.move_to(key.0, n);
+            Ok(key)
+        }
+
+        /// Remove the element at offset `n` in the random access sequence, shifting all later elements.
+        #element_vis fn remove_nth(&mut self, n: usize) -> Option<#element_name #types> {
+            let idx = self._random_access.nth(n)?;
+            Some(self._remove_at(idx))
+        }
+
+        #element_vis fn iter_random_access<'__mim_iter_lifetime>(&'__mim_iter_lifetime self) -> #random_access_name #iter_types {
+            #random_access_name {
+                _store_ref: &self._store,
+                _iter: self._random_access.iter(),
+            }
+        }
+    }
+}
+
+// TokenStream representing the iterator over the elements of a map with a random_access index, in sequence order.
+// This is named without an Iter suffix, so it can never collide with the iterator of a field.
+pub(crate) fn generate_random_access_iter(
+    extra_attrs: &ExtraAttributes,
+    random_access_name: &proc_macro2::Ident,
+    element_name: &proc_macro2::Ident,
+    element_vis: &Visibility,
+    generics: &Generics,
+    iter_generics: &Generics,
+) -> proc_macro2::TokenStream {
+    if !extra_attrs.random_access {
+        return quote! {};
+    }
+
+    let (_, types, _) = generics.split_for_impl();
+    let (iter_impls, iter_types, iter_where_clause) = iter_generics.split_for_impl();
+
+    quote! {
+        #element_vis struct #random_access_name #iter_impls #iter_where_clause {
+            _store_ref: &'__mim_iter_lifetime ::multi_index_map::slab::Slab<#element_name #types>,
+            _iter: ::std::slice::Iter<'__mim_iter_lifetime, usize>,
+        }
+
+        impl #iter_impls Iterator for #random_access_name #iter_types #iter_where_clause {
+            type Item = &'__mim_iter_lifetime #element_name #types;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                Some(&self._store_ref[*self._iter.next()?])
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self._iter.size_hint()
+            }
+
+            fn nth(&mut self, n: usize) -> Option<Self::Item> {
+                Some(&self._store_ref[*self._iter.nth(n)?])
+            }
+        }
+
+        impl #iter_impls DoubleEndedIterator for #random_access_name #iter_types #iter_where_clause {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                Some(&self._store_ref[*self._iter.next_back()?])
+            }
+        }
+
+        impl #iter_impls ExactSizeIterator for #random_access_name #iter_types #iter_where_clause {
+            fn len(&self) -> usize {
+                self._iter.len()
+            }
+        }
+
+        impl #iter_impls std::iter::FusedIterator for #random_access_name #iter_types #iter_where_clause {
+        }
+    }
+}
+
+// TokenStream representing the iterator returned by extract_if.
+// This walks the backing storage by position, removing and yielding each element matching the predicate.
+// Removing an element never moves any other element in the backing storage, so the walk can continue past it,
+//   and stops once every element present when the iterator was created has been visited.
+pub(crate) fn generate_extract_if(
+    extract_if_name: &proc_macro2::Ident,
+    map_name: &proc_macro2::Ident,
+    element_name: &proc_macro2::Ident,
+    element_vis: &Visibility,
+    generics: &Generics,
+    iter_generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+
+    let mut extract_if_generics = iter_generics.clone();
+    extract_if_generics
+        .params
+        .push(parse_quote!(__MimPredicate));
+    extract_if_generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(__MimPredicate: FnMut(&#element_name #types) -> bool));
+    let (extract_if_impls, extract_if_types, extract_if_where_clause) =
+        extract_if_generics.split_for_impl();
+
+    quote! {
+        #element_vis struct #extract_if_name #extract_if_impls #extract_if_where_clause {
+            _map: &'__mim_iter_lifetime mut #map_name #types,
+            _idx: usize,
+            _remaining: usize,
+            _pred: __MimPredicate,
+        }
+
+        impl #extract_if_impls Iterator for #extract_if_name #extract_if_types #extract_if_where_clause {
+            type Item = #element_name #types;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                while self._remaining > 0 {
+                    let idx = self._idx;
+                    self._idx += 1;
+                    if let Some(elem) = self._map._store.get(idx) {
+                        self._remaining -= 1;
+                        if (self._pred)(elem) {
+                            return Some(self._map._remove_at(idx));
+                        }
+                    }
+                }
+                None
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (0, Some(self._remaining))
+            }
+        }
+
+        impl #extract_if_impls std::iter::FusedIterator for #extract_if_name #extract_if_types #extract_if_where_clause {
+        }
+    }
+}
+
+// TokenStream representing the accessors through a key handle, ie. the position of the element
+//   in the backing storage, as returned by try_insert and insert.
+// These skip the lookup tables entirely, but otherwise behave like the accessors of a unique index.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn generate_key_accessors(
+    key_name: &Ident,
+    extra_attrs: &ExtraAttributes,
+    element_name: &Ident,
+    element_vis: &Visibility,
+    unindexed_types: &[&Type],
+    unindexed_idents: &[&Ident],
+    removes: &[proc_macro2::TokenStream],
+    pre_modifies: &[proc_macro2::TokenStream],
+    post_modifies: &[proc_macro2::TokenStream],
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+
+    // With generational handles, a key whose generation no longer matches its slot is stale,
+    //   ie. the element it referred to has been removed, and the slot may have been reused since.
+    let stale_check = extra_attrs.generational_handles.then(|| {
+        quote! {
+            if self._generations.get(key.0) != Some(&key.1) {
+                return None;
+            }
+        }
+    });
+
+    quote! {
+        /// Get the element at the given key, if it is still present in the map.
+        #element_vis fn get(&self, key: #key_name) -> Option<&#element_name #types> {
+            #stale_check
+            self._store.get(key.0)
+        }
+
+        /// Remove the element at the given key from the backing storage and all lookup tables.
+        #element_vis fn remove(&mut self, key: #key_name) -> Option<#element_name #types> {
+            #stale_check
+            let idx = key.0;
+            if !self._store.contains(idx) {
+                return None;
+            }
+            let elem_orig = self._store.remove(idx);
+            #(#removes)*
+            Some(elem_orig)
+        }
+
+        /// Modify the element at the given key, updating all lookup tables for any changed indexed fields.
+        #element_vis fn modify(
+            &mut self,
+            key: #key_name,
+            f: impl FnOnce(&mut #element_name #types)
+        ) -> Option<&#element_name #types> {
+            #stale_check
+            let idx = key.0;
+            let elem = self._store.get_mut(idx)?;
+            #(#pre_modifies)*
+            f(elem);
+            #(#post_modifies)*
+            Some(elem)
+        }
+
+        /// Update the unindexed fields of the element at the given key.
+        #element_vis fn update(
+            &mut self,
+            key: #key_name,
+            f: impl FnOnce(#(&mut #unindexed_types,)*)
+        ) -> Option<&#element_name #types> {
+            #stale_check
+            let elem = self._store.get_mut(key.0)?;
+            f(#(&mut elem.#unindexed_idents,)*);
+            Some(elem)
+        }
+    }
+}
+
+// TokenStream representing retain and extract_if, which remove every element matching a predicate.
+// Both use the removal of the element at a given position in the backing storage, which is generated here,
+//   and is also used by other accessors which find the position of the element to remove themselves.
+// This is kept private to the module the map is defined in, like the fields of the map.
+pub(crate) fn generate_retain(
+    extract_if_name: &Ident,
+    element_name: &Ident,
+    element_vis: &Visibility,
+    removes: &[proc_macro2::TokenStream],
+    generics: &Generics,
+    iter_generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+
+    let mut extract_if_generics = iter_generics.clone();
+    extract_if_generics
+        .params
+        .push(parse_quote!(__MimPredicate));
+    let (_, extract_if_types, _) = extract_if_generics.split_for_impl();
+
+    quote! {
+        fn _remove_at(&mut self, idx: usize) -> #element_name #types {
+            let elem_orig = self._store.remove(idx);
+            #(#removes)*
+            elem_orig
+        }
+
+        /// Remove every element for which the predicate returns false.
+        #element_vis fn retain(&mut self, mut f: impl FnMut(&#element_name #types) -> bool) {
+            self.extract_if(|elem| !f(elem)).for_each(drop);
+        }
+
+        /// Remove every element for which the predicate returns true, yielding the removed elements.
+        /// Elements are only removed as the returned iterator is advanced,
+        ///   so any elements left unvisited when it is dropped are kept.
+        #element_vis fn extract_if<'__mim_iter_lifetime, __MimPredicate: FnMut(&#element_name #types) -> bool>(
+            &'__mim_iter_lifetime mut self,
+            pred: __MimPredicate,
+        ) -> #extract_if_name #extract_if_types {
+            #extract_if_name {
+                _remaining: self._store.len(),
+                _map: self,
+                _idx: 0,
+                _pred: pred,
+            }
+        }
+    }
+}
+
+// TokenStream representing insert_or_replace.
+// Every element colliding with the new element in any unique index is removed and returned,
+//   so the new element can always be inserted, similar to the "last write wins" of HashMap::insert.
+pub(crate) fn generate_insert_or_replace(
+    element_name: &Ident,
+    element_vis: &Visibility,
+    removes: &[proc_macro2::TokenStream],
+    unique_lookups: &[proc_macro2::TokenStream],
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+    let unique_count = unique_lookups.len();
+
+    quote! {
+        #element_vis fn insert_or_replace(&mut self, elem: #element_name #types) -> Vec<#element_name #types> {
+            let lookups: [(&'static str, Option<usize>); #unique_count] = [#(#unique_lookups,)*];
+            let mut displaced_idxs = ::std::vec::Vec::new();
+            for (_index, existing) in lookups {
+                if let Some(existing) = existing {
+                    if !displaced_idxs.contains(&existing) {
+                        displaced_idxs.push(existing);
+                    }
+                }
             }
-        }
 
-        impl #iter_impls ExactSizeIterator for #iter_mut_name #iter_types #iter_where_clause {
-            fn len(&self) -> usize {
-                self.0.len()
+            let mut displaced = ::std::vec::Vec::with_capacity(displaced_idxs.len());
+            for idx in displaced_idxs {
+                let elem_orig = self._store.remove(idx);
+                #(#removes)*
+                displaced.push(elem_orig);
             }
-        }
-
-        impl #iter_impls std::iter::FusedIterator for #iter_mut_name #iter_types #iter_where_clause {
+            self.insert(elem);
+            displaced
         }
     }
 }
@@ -773,6 +2605,12 @@ pub(crate) fn generate_accessors<'a>(
     removes: &'a [proc_macro2::TokenStream],
     pre_modifies: &'a [proc_macro2::TokenStream],
     post_modifies: &'a [proc_macro2::TokenStream],
+    uniqueness_checks: &'a [proc_macro2::TokenStream],
+    unique_lookups: &'a [proc_macro2::TokenStream],
+    occupied_entry_name: &'a Ident,
+    matches_name: &'a Ident,
+    key_name: &'a Ident,
+    existing_handle: &'a proc_macro2::TokenStream,
     generics: &'a Generics,
     iter_generics: &'a Generics,
 ) -> impl Iterator<Item = proc_macro2::TokenStream> + 'a {
@@ -832,23 +2670,125 @@ pub(crate) fn generate_accessors<'a>(
                 generics,
             );
 
+            let try_modifier = generate_field_try_modifier(
+                idents,
+                &field_info,
+                element_name,
+                uniqueness,
+                pre_modifies,
+                post_modifies,
+                uniqueness_checks,
+                key_name,
+                existing_handle,
+                generics,
+            );
+
+            let upserter = generate_field_upserter(
+                idents,
+                &field_info,
+                element_name,
+                uniqueness,
+                removes,
+                unique_lookups,
+                key_name,
+                existing_handle,
+                generics,
+            );
+
+            let entry_getter = generate_field_entry_getter(
+                idents,
+                &field_info,
+                uniqueness,
+                occupied_entry_name,
+                existing_handle,
+                iter_generics,
+            );
+
+            let iter_by_key_getter = generate_field_iter_by_key_getter(
+                idents,
+                &field_info,
+                ordering,
+                uniqueness,
+                matches_name,
+                iter_generics,
+            );
+
+            let counters = generate_field_counters(idents, &field_info, ordering, uniqueness);
+
+            let keys_getter =
+                generate_field_keys_getter(idents, &field_info, ordering, uniqueness, iter_generics);
+
+            let range_modifiers = generate_field_range_modifiers(
+                idents,
+                &field_info,
+                element_name,
+                ordering,
+                uniqueness,
+                existing_handle,
+                generics,
+            );
+
+            let splitter = generate_field_splitter(idents, &field_info, ordering, uniqueness);
+
+            let first_last_getters = generate_field_first_last_getters(
+                idents,
+                &field_info,
+                element_name,
+                ordering,
+                uniqueness,
+                generics,
+            );
+
+            let rank_getters =
+                generate_field_rank_getters(idents, &field_info, element_name, generics);
+
             let iter_getter =
                 generate_field_iter_getter(idents, &field_info, ordering, iter_generics);
 
+            let range_getter = generate_field_range_getter(
+                idents,
+                &field_info,
+                ordering,
+                uniqueness,
+                iter_generics,
+            );
+
             // Put all these TokenStreams together, and put a TokenStream representing the iter_by_ accessor
             //   on the end.
             quote! {
                 #getter
 
+                #iter_by_key_getter
+
+                #counters
+
                 #mut_getter
 
                 #remover
 
                 #modifier
 
+                #try_modifier
+
                 #updater
 
+                #upserter
+
+                #entry_getter
+
                 #iter_getter
+
+                #keys_getter
+
+                #first_last_getters
+
+                #range_getter
+
+                #rank_getters
+
+                #range_modifiers
+
+                #splitter
             }
         })
 }
@@ -859,6 +2799,7 @@ pub(crate) fn generate_accessors<'a>(
 pub(crate) fn generate_iterators<'a>(
     fields: &'a [(Field, FieldIdents, Ordering, Uniqueness)],
     element_name: &'a proc_macro2::Ident,
+    matches_name: &'a proc_macro2::Ident,
     generics: &'a Generics,
     iter_generics: &'a Generics,
 ) -> impl Iterator<Item = proc_macro2::TokenStream> + 'a {
@@ -874,19 +2815,20 @@ pub(crate) fn generate_iterators<'a>(
         );
         let iter_name = &idents.iter_name;
         let ty = &f.ty;
+        let lookup_ty = lookup_key_type(idents, ty);
 
         // TokenStream representing the actual type of the iterator
         let iter_type = match uniqueness {
             Uniqueness::Unique => match ordering {
-                Ordering::Hashed => quote! {::std::collections::hash_map::Iter<'__mim_iter_lifetime, #ty, usize>},
-                Ordering::Ordered => quote! {::std::collections::btree_map::Iter<'__mim_iter_lifetime, #ty, usize>},
+                Ordering::Hashed => quote! {::std::collections::hash_map::Iter<'__mim_iter_lifetime, #lookup_ty, usize>},
+                Ordering::Ordered => quote! {::std::collections::btree_map::Iter<'__mim_iter_lifetime, #lookup_ty, usize>},
             },
             Uniqueness::NonUnique => match ordering {
                 Ordering::Hashed => {
-                    quote! {::std::collections::hash_map::Iter<'__mim_iter_lifetime, #ty, ::std::collections::BTreeSet::<usize>>}
+                    quote! {::std::collections::hash_map::Iter<'__mim_iter_lifetime, #lookup_ty, ::std::collections::BTreeSet::<usize>>}
                 }
                 Ordering::Ordered => {
-                    quote! {::std::collections::btree_map::Iter<'__mim_iter_lifetime, #ty, ::std::collections::BTreeSet::<usize>>}
+                    quote! {::std::collections::btree_map::Iter<'__mim_iter_lifetime, #lookup_ty, ::std::collections::BTreeSet::<usize>>}
                 }
             },
         };
@@ -934,6 +2876,136 @@ pub(crate) fn generate_iterators<'a>(
             },
         };
 
+        // TokenStream representing the iterator over a range of keys of an ordered index, returned by range_by_.
+        // Unlike the full iterator, both ends walk the same btree_map::Range, so they meet in the middle.
+        // For non-unique indexes we keep separate inner iterators over the matching elements at the front and
+        //   back, and once the range is exhausted each end drains whatever the other end has left.
+        let range_iter_name = &idents.range_iter_name;
+        let range_iterator = match uniqueness {
+            Uniqueness::Unique => quote! {
+                #field_vis struct #range_iter_name #iter_impls #iter_where_clause {
+                    _store_ref: &'__mim_iter_lifetime ::multi_index_map::slab::Slab<#element_name #element_types>,
+                    _range: ::std::collections::btree_map::Range<'__mim_iter_lifetime, #lookup_ty, usize>,
+                }
+
+                impl #iter_impls Iterator for #range_iter_name #iter_types #iter_where_clause {
+                    type Item = &'__mim_iter_lifetime #element_name #element_types;
+                    fn next(&mut self) -> Option<Self::Item> {
+                        Some(&self._store_ref[*self._range.next()?.1])
+                    }
+                }
+
+                impl #iter_impls DoubleEndedIterator for #range_iter_name #iter_types #iter_where_clause {
+                    fn next_back(&mut self) -> Option<Self::Item> {
+                        Some(&self._store_ref[*self._range.next_back()?.1])
+                    }
+                }
+            },
+            Uniqueness::NonUnique => quote! {
+                #field_vis struct #range_iter_name #iter_impls #iter_where_clause {
+                    _store_ref: &'__mim_iter_lifetime ::multi_index_map::slab::Slab<#element_name #element_types>,
+                    _range: ::std::collections::btree_map::Range<'__mim_iter_lifetime, #lookup_ty, ::std::collections::BTreeSet<usize>>,
+                    _front_iter: Option<::std::collections::btree_set::Iter<'__mim_iter_lifetime, usize>>,
+                    _back_iter: Option<::std::collections::btree_set::Iter<'__mim_iter_lifetime, usize>>,
+                }
+
+                impl #iter_impls Iterator for #range_iter_name #iter_types #iter_where_clause {
+                    type Item = &'__mim_iter_lifetime #element_name #element_types;
+                    fn next(&mut self) -> Option<Self::Item> {
+                        loop {
+                            if let Some(idx) = self._front_iter.as_mut().and_then(Iterator::next) {
+                                return Some(&self._store_ref[*idx]);
+                            }
+                            match self._range.next() {
+                                Some((_, idxs)) => self._front_iter = Some(idxs.iter()),
+                                None => {
+                                    let idx = self._back_iter.as_mut()?.next()?;
+                                    return Some(&self._store_ref[*idx]);
+                                }
+                            }
+                        }
+                    }
+                }
+
+                impl #iter_impls DoubleEndedIterator for #range_iter_name #iter_types #iter_where_clause {
+                    fn next_back(&mut self) -> Option<Self::Item> {
+                        loop {
+                            if let Some(idx) = self._back_iter.as_mut().and_then(DoubleEndedIterator::next_back) {
+                                return Some(&self._store_ref[*idx]);
+                            }
+                            match self._range.next_back() {
+                                Some((_, idxs)) => self._back_iter = Some(idxs.iter()),
+                                None => {
+                                    let idx = self._front_iter.as_mut()?.next_back()?;
+                                    return Some(&self._store_ref[*idx]);
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+        };
+
+        // TokenStream representing the iterator over the groups of each distinct key, returned by groups_by_.
+        // Each group is itself an iterator over the elements with that key, which is a single element for unique
+        //   indexes, or the shared iterator over a set of matching elements for non-unique indexes.
+        let groups_name = &idents.groups_name;
+        let groups_iter_type = lookup_table_iter_type(&lookup_ty, ordering, uniqueness, &format_ident!("Iter"));
+        let (group_type, make_group) = match uniqueness {
+            Uniqueness::Unique => (
+                quote! { ::std::iter::Once<&'__mim_iter_lifetime #element_name #element_types> },
+                quote! { ::std::iter::once(&self._store_ref[*idx]) },
+            ),
+            Uniqueness::NonUnique => (
+                quote! { #matches_name #iter_types },
+                quote! {
+                    #matches_name {
+                        _store_ref: self._store_ref,
+                        _iter: Some(idx.iter()),
+                    }
+                },
+            ),
+        };
+        let group_key = unwrap_key_ref(idents, quote! { key });
+        let groups_rev = match ordering {
+            Ordering::Hashed => quote! {},
+            Ordering::Ordered => quote! {
+                impl #iter_impls DoubleEndedIterator for #groups_name #iter_types #iter_where_clause {
+                    fn next_back(&mut self) -> Option<Self::Item> {
+                        let (key, idx) = self._iter.next_back()?;
+                        Some((#group_key, #make_group))
+                    }
+                }
+            },
+        };
+        let groups_iterator = quote! {
+            #field_vis struct #groups_name #iter_impls #iter_where_clause {
+                _store_ref: &'__mim_iter_lifetime ::multi_index_map::slab::Slab<#element_name #element_types>,
+                _iter: #groups_iter_type,
+            }
+
+            impl #iter_impls Iterator for #groups_name #iter_types #iter_where_clause {
+                type Item = (&'__mim_iter_lifetime #ty, #group_type);
+
+                fn next(&mut self) -> Option<Self::Item> {
+                    let (key, idx) = self._iter.next()?;
+                    Some((#group_key, #make_group))
+                }
+
+                fn size_hint(&self) -> (usize, Option<usize>) {
+                    self._iter.size_hint()
+                }
+            }
+
+            #groups_rev
+
+            impl #iter_impls ExactSizeIterator for #groups_name #iter_types #iter_where_clause {
+                fn len(&self) -> usize {
+                    self._iter.len()
+                }
+            }
+        };
+
         // TokenStream representing the iterator over each indexed field.
         // We have a different iterator type for each indexed field. Each one wraps the standard Iterator for
         //   that lookup table, but adds in a couple of things:
@@ -957,6 +3029,8 @@ pub(crate) fn generate_iterators<'a>(
                         #iter_action
                     }
                 }
+
+                #groups_iterator
             },
             Ordering::Ordered => quote! {
                 #field_vis struct #iter_name #iter_impls #iter_where_clause {
@@ -978,11 +3052,229 @@ pub(crate) fn generate_iterators<'a>(
                         #rev_iter_action
                     }
                 }
+
+                #range_iterator
+
+                #groups_iterator
             },
         }
     })
 }
 
+// TokenStream representing the Entry types returned by the entry_by_ accessors of unique indexes.
+// The occupied entry holds the position of the element in the backing storage, so is shared by all fields,
+//   and its methods go through the key handle accessors rather than looking up the key again.
+// Each unique field has its own vacant entry and its own Entry enum.
+// The vacant entry borrows the fields of the map separately, so it can hold the entry of its own lookup table
+//   alongside the backing storage and every other lookup table.
+// Inserting through it checks that the element has the key of the entry, then fills the held entry,
+//   and inserts to every other lookup table as usual, so any collision in another unique index is still returned,
+//   wrapped in a VacantEntryError.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn generate_entries<'a>(
+    fields: &'a [(Field, FieldIdents, Ordering, Uniqueness)],
+    extra_attrs: &'a ExtraAttributes,
+    map_name: &'a Ident,
+    occupied_entry_name: &'a Ident,
+    key_name: &'a Ident,
+    existing_handle: &'a proc_macro2::TokenStream,
+    element_name: &'a Ident,
+    element_vis: &'a Visibility,
+    unindexed_types: &'a [&Type],
+    generics: &'a Generics,
+    iter_generics: &'a Generics,
+) -> proc_macro2::TokenStream {
+    let (_, types, _) = generics.split_for_impl();
+    let (iter_impls, iter_types, iter_where_clause) = iter_generics.split_for_impl();
+    let elem = format_ident!("elem");
+
+    // The fields of the map, other than the backing storage and the lookup tables, which inserting has to update.
+    let mut extra_fields = Vec::new();
+    let mut extra_inserts = Vec::new();
+    if extra_attrs.generational_handles {
+        extra_fields.push((
+            format_ident!("_generations"),
+            quote! { ::std::vec::Vec<u32> },
+        ));
+        extra_inserts.push(quote! {
+            if idx == self._generations.len() {
+                self._generations.push(0);
+            }
+        });
+    }
+    if extra_attrs.sequenced {
+        extra_fields.push((
+            format_ident!("_sequence"),
+            quote! { ::multi_index_map::sequence::Sequence },
+        ));
+        extra_inserts.push(quote! { self._sequence.push_back(idx); });
+    }
+    if extra_attrs.random_access {
+        extra_fields.push((
+            format_ident!("_random_access"),
+            quote! { ::multi_index_map::random_access::RandomAccess },
+        ));
+        extra_inserts.push(quote! { self._random_access.push(idx); });
+    }
+
+    let field_entries = fields
+        .iter()
+        .filter(|(_f, _idents, _ordering, uniqueness)| matches!(uniqueness, Uniqueness::Unique))
+        .map(|(f, idents, ordering, _uniqueness)| {
+            let field_vis = &f.vis;
+            let field_type = &f.ty;
+            let field_name_string = idents.name.to_string();
+            let index_name = &idents.index_name;
+            let entry_name = &idents.entry_name;
+            let vacant_entry_name = &idents.vacant_entry_name;
+            let lookup_key_type = lookup_key_type(idents, &f.ty);
+            let table_entry = match ordering {
+                Ordering::Hashed => quote! { ::std::collections::hash_map },
+                Ordering::Ordered => quote! { ::std::collections::btree_map },
+            };
+
+            // Every other lookup table is borrowed under the name of its field in the map,
+            //   so inserting to it is generated exactly as in try_insert.
+            let is_other = |(_f, other, _ordering, _uniqueness): &&(Field, FieldIdents, Ordering, Uniqueness)| {
+                other.index_name != idents.index_name
+            };
+            let (part_names, part_types): (Vec<_>, Vec<_>) = fields
+                .iter()
+                .filter(is_other)
+                .flat_map(|(f, other, ordering, uniqueness)| {
+                    lookup_table_field_types(f, other, ordering, uniqueness, extra_attrs)
+                        .map(|(name, ty)| (name.clone(), ty))
+                })
+                .chain(extra_fields.iter().cloned())
+                .unzip();
+            let entries_for_insert = generate_entries_for_insert(fields, existing_handle)
+                .zip(fields)
+                .filter(|(_entry, field)| is_other(field))
+                .map(|(entry, _field)| entry);
+            let inserts_for_entries = generate_inserts_for_entries(fields)
+                .zip(fields)
+                .filter(|(_insert, field)| is_other(field))
+                .map(|(insert, _field)| insert);
+            let key = wrap_key_ref(idents, key_ref(idents, &elem));
+            let key_mismatch = match filter_call(idents, &elem) {
+                // An element without a key in this index, as it is filtered out or its sparse field is None,
+                //   cannot match the key of the entry.
+                Some(filter) => quote! { !(#filter) || #key != self._entry.key() },
+                None => quote! { #key != self._entry.key() },
+            };
+            let entry_key = unwrap_key_ref(idents, quote! { self._entry.key() });
+            let into_entry_key = unwrap_key(idents, quote! { self._entry.into_key() });
+            let wrapped_key = wrap_key(idents, quote! { key });
+
+            quote! {
+                #field_vis enum #entry_name #iter_impls #iter_where_clause {
+                    Occupied(#occupied_entry_name #iter_types),
+                    Vacant(#vacant_entry_name #iter_types),
+                }
+
+                #field_vis struct #vacant_entry_name #iter_impls #iter_where_clause {
+                    _entry: #table_entry::VacantEntry<'__mim_iter_lifetime, #lookup_key_type, usize>,
+                    _store: &'__mim_iter_lifetime mut ::multi_index_map::slab::Slab<#element_name #types>,
+                    #(#part_names: &'__mim_iter_lifetime mut #part_types,)*
+                }
+
+                impl #iter_impls #vacant_entry_name #iter_types #iter_where_clause {
+                    // Only called by entry_by_ once the key has been found to be vacant.
+                    fn _new(map: &'__mim_iter_lifetime mut #map_name #types, key: #field_type) -> Self {
+                        let #map_name { _store, #index_name, #(#part_names,)* .. } = map;
+                        let _entry = match #index_name.entry(#wrapped_key) {
+                            #table_entry::Entry::Vacant(e) => e,
+                            #table_entry::Entry::Occupied(_) => {
+                                panic!("Internal invariants broken, vacant entry key present in index '{}'", #field_name_string)
+                            }
+                        };
+                        Self { _entry, _store, #(#part_names,)* }
+                    }
+
+                    #field_vis fn key(&self) -> &#field_type {
+                        #entry_key
+                    }
+
+                    #field_vis fn into_key(self) -> #field_type {
+                        #into_entry_key
+                    }
+
+                    /// Insert an element, which must have the key this entry was created with.
+                    /// The element is added to this index through the entry, without looking up the key again,
+                    ///   and to every other index as `try_insert` does.
+                    /// Returns an error handing back the element if its key does not match the key of this entry,
+                    ///   or if it would violate a uniqueness constraint in another index.
+                    #field_vis fn insert(
+                        self,
+                        elem: #element_name #types,
+                    ) -> Result<&'__mim_iter_lifetime #element_name #types, ::multi_index_map::VacantEntryError<#element_name #types, #key_name>> {
+                        if #key_mismatch {
+                            return Err(::multi_index_map::VacantEntryError::KeyMismatch(elem));
+                        }
+                        Ok(self._insert(elem)?)
+                    }
+
+                    fn _insert(
+                        mut self,
+                        elem: #element_name #types,
+                    ) -> Result<&'__mim_iter_lifetime #element_name #types, ::multi_index_map::UniquenessError<#element_name #types, #key_name>> {
+                        let store_entry = self._store.vacant_entry();
+                        let idx = store_entry.key();
+
+                        #(#entries_for_insert)*
+                        #(#inserts_for_entries)*
+
+                        self._entry.insert(idx);
+                        store_entry.insert(elem);
+                        #(#extra_inserts)*
+
+                        let store: &'__mim_iter_lifetime ::multi_index_map::slab::Slab<#element_name #types> = self._store;
+                        Ok(&store[idx])
+                    }
+                }
+            }
+        });
+
+    quote! {
+        #element_vis struct #occupied_entry_name #iter_impls #iter_where_clause {
+            _map: &'__mim_iter_lifetime mut #map_name #types,
+            _key: #key_name,
+        }
+
+        impl #iter_impls #occupied_entry_name #iter_types #iter_where_clause {
+            #element_vis fn get(&self) -> &#element_name #types {
+                &self._map._store[self._key.0]
+            }
+
+            #element_vis fn into_ref(self) -> &'__mim_iter_lifetime #element_name #types {
+                &self._map._store[self._key.0]
+            }
+
+            #element_vis fn key(&self) -> #key_name {
+                self._key
+            }
+
+            /// Update the unindexed fields of the element.
+            #element_vis fn update(&mut self, f: impl FnOnce(#(&mut #unindexed_types,)*)) -> &#element_name #types {
+                self._map.update(self._key, f).expect("Internal invariants broken, occupied entry element is missing")
+            }
+
+            /// Modify the element, updating all lookup tables for any changed indexed fields.
+            /// This consumes the entry, as the element may no longer have the key the entry was created with.
+            #element_vis fn modify(self, f: impl FnOnce(&mut #element_name #types)) -> &'__mim_iter_lifetime #element_name #types {
+                self._map.modify(self._key, f).expect("Internal invariants broken, occupied entry element is missing")
+            }
+
+            /// Remove the element from the backing storage and all lookup tables.
+            #element_vis fn remove(self) -> #element_name #types {
+                self._map.remove(self._key).expect("Internal invariants broken, occupied entry element is missing")
+            }
+        }
+
+        #(#field_entries)*
+    }
+}
+
 // Build the final output using quasi-quoting
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn generate_expanded(
@@ -995,7 +3287,7 @@ pub(crate) fn generate_expanded(
     inserts_for_entries: impl Iterator<Item = proc_macro2::TokenStream>,
     accessors: impl Iterator<Item = proc_macro2::TokenStream>,
     iterators: impl Iterator<Item = proc_macro2::TokenStream>,
-    clears: impl Iterator<Item = proc_macro2::TokenStream>,
+    clears: &[proc_macro2::TokenStream],
     lookup_table_fields: impl Iterator<Item = proc_macro2::TokenStream>,
     lookup_table_fields_init: impl Iterator<Item = proc_macro2::TokenStream>,
     lookup_table_fields_default: impl Iterator<Item = proc_macro2::TokenStream>,
@@ -1004,6 +3296,18 @@ pub(crate) fn generate_expanded(
     iter_mut_name: &proc_macro2::Ident,
     iter_mut: proc_macro2::TokenStream,
     iter_generics: &Generics,
+    key_name: &proc_macro2::Ident,
+    key_accessors: proc_macro2::TokenStream,
+    insert_or_replace: proc_macro2::TokenStream,
+    entries: proc_macro2::TokenStream,
+    retain: proc_macro2::TokenStream,
+    extract_if: proc_macro2::TokenStream,
+    matches: proc_macro2::TokenStream,
+    sequenced_accessors: proc_macro2::TokenStream,
+    sequenced_iter: proc_macro2::TokenStream,
+    random_access_accessors: proc_macro2::TokenStream,
+    random_access_iter: proc_macro2::TokenStream,
+    comparators: &[proc_macro2::TokenStream],
 ) -> proc_macro2::TokenStream {
     let derives = &extra_attrs.derives;
     let (impls, types, where_clause) = generics.split_for_impl();
@@ -1011,19 +3315,111 @@ pub(crate) fn generate_expanded(
 
     let foreign_iter_type = Ident::new("__ForeignIter", proc_macro2::Span::call_site());
 
+    // With generational handles, the generation of each slot in the backing storage is stored alongside it.
+    // This is never shrunk, so the generation of a slot survives even if the backing storage is shrunk.
+    let new_key = key_handle(key_name, extra_attrs, &format_ident!("idx"));
+    let (key_def, generations_field, generations_init, generations_clear, insert_result) =
+        if extra_attrs.generational_handles {
+            (
+                quote! { #element_vis struct #key_name(usize, u32); },
+                quote! { _generations: ::std::vec::Vec<u32>, },
+                quote! { _generations: ::std::vec::Vec::new(), },
+                quote! {
+                    for generation in &mut self._generations {
+                        *generation = generation.wrapping_add(1);
+                    }
+                },
+                quote! {
+                    if idx == self._generations.len() {
+                        self._generations.push(0);
+                    }
+                    Ok(#new_key)
+                },
+            )
+        } else {
+            (
+                quote! { #element_vis struct #key_name(usize); },
+                quote! {},
+                quote! {},
+                quote! {},
+                quote! { Ok(#new_key) },
+            )
+        };
+
+    // With a sequenced index, every element is linked into the sequence as it is inserted.
+    let (sequence_field, sequence_init, sequence_clear, sequence_insert) = if extra_attrs.sequenced {
+        (
+            quote! { _sequence: ::multi_index_map::sequence::Sequence, },
+            quote! { _sequence: ::multi_index_map::sequence::Sequence::default(), },
+            quote! { self._sequence.clear(); },
+            quote! { self._sequence.push_back(idx); },
+        )
+    } else {
+        (quote! {}, quote! {}, quote! {}, quote! {})
+    };
+
+    // Likewise with a random_access index, every element is appended to the dense sequence of positions.
+    let (random_access_field, random_access_init, random_access_clear, random_access_insert) =
+        if extra_attrs.random_access {
+            (
+                quote! { _random_access: ::multi_index_map::random_access::RandomAccess, },
+                quote! { _random_access: ::multi_index_map::random_access::RandomAccess::default(), },
+                quote! { self._random_access.clear(); },
+                quote! { self._random_access.push(idx); },
+            )
+        } else {
+            (quote! {}, quote! {}, quote! {}, quote! {})
+        };
+
+    // The order of the map itself, which elements moved into a new map are inserted in, so that it is kept.
+    // This is the order of the sequenced index if there is one, then of the random_access index,
+    //   otherwise the order of the backing storage.
+    let sort_positions = if extra_attrs.sequenced {
+        quote! {
+            let moved: ::std::collections::BTreeSet<usize> = positions.into_iter().collect();
+            self._sequence.iter().filter(|idx| moved.contains(idx)).collect()
+        }
+    } else if extra_attrs.random_access {
+        quote! {
+            let mut positions = positions;
+            positions.sort_unstable_by_key(|&idx| self._random_access.offset_of(idx));
+            positions
+        }
+    } else {
+        quote! {
+            let mut positions = positions;
+            positions.sort_unstable();
+            positions
+        }
+    };
+
+    let key_doc = format!(
+        "Handle to an element in a [`{map_name}`], returned when the element is inserted.\n\
+        The handle stays valid until the element is removed, regardless of changes to its indexed fields."
+    );
+
     let mut generics_with_iterator_lifetime;
 
     let impls_with_iterator_lifetime = {
         generics_with_iterator_lifetime = generics.clone();
-        generics_with_iterator_lifetime.params.push(parse_quote!('__mim_iter_lifetime));
+        generics_with_iterator_lifetime
+            .params
+            .push(parse_quote!('__mim_iter_lifetime));
         let (impls_with_iterator_lifetime, _, _) = generics_with_iterator_lifetime.split_for_impl();
         impls_with_iterator_lifetime
     };
 
     quote! {
+        #[doc = #key_doc]
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+        #key_def
+
         #(#[#derives])*
         #element_vis struct #map_name #impls {
             _store: ::multi_index_map::slab::Slab<#element_name #types>,
+            #generations_field
+            #sequence_field
+            #random_access_field
             #(#lookup_table_fields)*
         }
 
@@ -1031,6 +3427,9 @@ pub(crate) fn generate_expanded(
             fn default() -> Self {
                 Self {
                     _store: ::multi_index_map::slab::Slab::default(),
+                    #generations_init
+                    #sequence_init
+                    #random_access_init
                     #(#lookup_table_fields_default)*
                 }
             }
@@ -1040,6 +3439,9 @@ pub(crate) fn generate_expanded(
             #element_vis fn with_capacity(n: usize) -> Self {
                 Self {
                     _store: ::multi_index_map::slab::Slab::with_capacity(n),
+                    #generations_init
+                    #sequence_init
+                    #random_access_init
                     #(#lookup_table_fields_init)*
                 }
             }
@@ -1068,27 +3470,62 @@ pub(crate) fn generate_expanded(
                 #(#lookup_table_fields_shrink)*
             }
 
-            #element_vis fn try_insert(&mut self, elem: #element_name #types) -> Result<&#element_name #types, ::multi_index_map::UniquenessError<#element_name #types>> {
+            #element_vis fn try_insert(&mut self, elem: #element_name #types) -> Result<#key_name, ::multi_index_map::UniquenessError<#element_name #types, #key_name>> {
                 let store_entry = self._store.vacant_entry();
                 let idx = store_entry.key();
 
                 #(#entries_for_insert)*
                 #(#inserts_for_entries)*
 
-                let elem = store_entry.insert(elem);
-
-                Ok(elem)
+                store_entry.insert(elem);
+                #sequence_insert
+                #random_access_insert
+                #insert_result
             }
 
-            #element_vis fn insert(&mut self, elem: #element_name #types) -> &#element_name #types {
+            #element_vis fn insert(&mut self, elem: #element_name #types) -> #key_name {
                 self.try_insert(elem).expect("Unable to insert element")
             }
 
+            fn _in_map_order(&self, positions: ::std::vec::Vec<usize>) -> ::std::vec::Vec<usize> {
+                #sort_positions
+            }
+
+            #insert_or_replace
+
+            /// Move every element of `other` into this map, leaving `other` empty.
+            /// Elements which would violate a uniqueness constraint are not inserted, and are returned instead.
+            #element_vis fn append(&mut self, other: &mut Self) -> Vec<::multi_index_map::UniquenessError<#element_name #types, #key_name>> {
+                self.reserve(other.len());
+                other.drain().filter_map(|elem| self.try_insert(elem).err()).collect()
+            }
+
+            #key_accessors
+
+            #retain
+
+            #sequenced_accessors
+
+            #random_access_accessors
+
             #element_vis fn clear(&mut self) {
                 self._store.clear();
+                #generations_clear
+                #sequence_clear
+                #random_access_clear
                 #(#clears)*
             }
 
+            /// Remove all elements from the map, yielding them by value.
+            /// The lookup tables are cleared immediately, and any elements not yielded are dropped with the iterator.
+            #element_vis fn drain(&mut self) -> ::multi_index_map::slab::Drain<#element_name #types> {
+                #generations_clear
+                #sequence_clear
+                #random_access_clear
+                #(#clears)*
+                self._store.drain()
+            }
+
             // Allow iteration directly over the backing storage
             #element_vis fn iter(&self) -> ::multi_index_map::slab::Iter<#element_name #types> {
                 self._store.iter()
@@ -1107,6 +3544,14 @@ pub(crate) fn generate_expanded(
 
         #iter_mut
 
+        #extract_if
+
+        #matches
+
+        #sequenced_iter
+
+        #random_access_iter
+
         impl #impls_with_iterator_lifetime IntoIterator for &'__mim_iter_lifetime #map_name #types {
             type Item = (usize, &'__mim_iter_lifetime #element_name #types);
             type IntoIter = ::multi_index_map::slab::Iter<'__mim_iter_lifetime, #element_name #types>;
@@ -1115,6 +3560,17 @@ pub(crate) fn generate_expanded(
             }
         }
 
+        impl #impls IntoIterator for #map_name #types #where_clause {
+            type Item = #element_name #types;
+            type IntoIter = ::std::iter::Map<
+                ::multi_index_map::slab::IntoIter<#element_name #types>,
+                fn((usize, #element_name #types)) -> #element_name #types,
+            >;
+            fn into_iter(self) -> Self::IntoIter {
+                self._store.into_iter().map(|(_, elem)| elem)
+            }
+        }
+
         /// Build a #map_name from any IntoIterator of elements.
         /// Among any sets of duplicate elements, "duplicate" referring to any unique index or indices,
         /// the _first_ such element silently wins the conflict,
@@ -1133,5 +3589,51 @@ pub(crate) fn generate_expanded(
 
         #(#iterators)*
 
+        #entries
+
+        #(#comparators)*
+    }
+}
+
+// TokenStream representing the marker type of an ordered index with `compare = "path::to::cmp"`, which implements
+//   KeyOrder for the key type `ty` by calling the compare function, with its arguments swapped if `desc` is set.
+// The marker appears in the types of the iterators over the lookup table, so has the visibility of the element.
+// The key type and compare function may use the generics of the element, so the marker carries them too,
+//   through a PhantomData as the marker holds no values.
+pub(crate) fn generate_comparator(
+    comparator_name: &Ident,
+    compare_fn: &proc_macro2::TokenStream,
+    desc: bool,
+    ty: &Type,
+    element_vis: &Visibility,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let (impls, types, where_clause) = generics.split_for_impl();
+    let phantoms = generics.params.iter().filter_map(|param| match param {
+        syn::GenericParam::Type(param) => {
+            let ident = &param.ident;
+            Some(quote! { #ident })
+        }
+        syn::GenericParam::Lifetime(param) => {
+            let lifetime = &param.lifetime;
+            Some(quote! { &#lifetime () })
+        }
+        syn::GenericParam::Const(_) => None,
+    });
+    let call = if desc {
+        quote! { #compare_fn(b, a) }
+    } else {
+        quote! { #compare_fn(a, b) }
+    };
+
+    quote! {
+        #[doc(hidden)]
+        #element_vis struct #comparator_name #impls (::std::marker::PhantomData<fn() -> (#(#phantoms,)*)>) #where_clause;
+
+        impl #impls ::multi_index_map::KeyOrder<#ty> for #comparator_name #types #where_clause {
+            fn cmp(a: &#ty, b: &#ty) -> ::std::cmp::Ordering {
+                #call
+            }
+        }
     }
 }
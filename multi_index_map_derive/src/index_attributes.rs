@@ -2,7 +2,8 @@ use ::syn::Field;
 use proc_macro2::Span;
 use proc_macro_error2::emit_error;
 use syn::{
-    punctuated::Punctuated, spanned::Spanned, DeriveInput, Meta, MetaList, NestedMeta, Path,
+    punctuated::Punctuated, spanned::Spanned, DeriveInput, Ident, Meta, MetaList, NestedMeta, Path,
+    Type,
 };
 
 // Represents whether the index is Ordered or Hashed, ie. whether we use a BTreeMap or a FxHashMap
@@ -23,7 +24,6 @@ pub(crate) enum Uniqueness {
 
 // Get the Ordering and Uniqueness for a given field attribute.
 pub(crate) fn get_index_kind(f: &Field) -> Option<(Ordering, Uniqueness)> {
-    let mut ident_buf = String::new();
     for attr in &f.attrs {
         if attr.path.is_ident("multi_index") {
             return {
@@ -31,31 +31,379 @@ pub(crate) fn get_index_kind(f: &Field) -> Option<(Ordering, Uniqueness)> {
                 let nested = meta_list.nested.first()?;
                 let syn::NestedMeta::Meta(syn::Meta::Path(nested_path)) = nested else { return None };
 
-                match nested_path.get_ident().map(|i| { ident_buf = i.to_string(); &*ident_buf }) {
-                    Some("hashed_unique") => Some((Ordering::Hashed, Uniqueness::Unique)),
-                    Some("ordered_unique") => Some((Ordering::Ordered, Uniqueness::Unique)),
-                    Some("hashed_non_unique") => Some((Ordering::Hashed, Uniqueness::NonUnique)),
-                    Some("ordered_non_unique") => Some((Ordering::Ordered, Uniqueness::NonUnique)),
-                    _ => {
-                        emit_error!(nested_path.span(), "Invalid multi_index attribute, should be one of [hashed_unique, ordered_unique, hashed_non_unique, ordered_non_unique]");
-                        None
+                parse_index_kind(nested_path)
+            }
+        }
+    }
+    None
+}
+
+// Get whether a field's index is `ordered_ranked`, ie. an ordered non-unique index which also keeps
+//   an order-statistic tree, to answer rank queries.
+pub(crate) fn is_ranked(f: &Field) -> bool {
+    has_index_kind(f, "ordered_ranked")
+}
+
+// Get whether a field's index is `hashed_multi` or `ordered_multi`, ie. a non-unique index on a collection field,
+//   under every item of which the element is indexed.
+pub(crate) fn is_multi(f: &Field) -> bool {
+    has_index_kind(f, "hashed_multi") || has_index_kind(f, "ordered_multi")
+}
+
+fn has_index_kind(f: &Field, kind: &str) -> bool {
+    f.attrs
+        .iter()
+        .find(|attr| attr.path.is_ident("multi_index"))
+        .and_then(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::List(meta_list)) => meta_list.nested.first().cloned(),
+            _ => None,
+        })
+        .map_or(false, |nested| {
+            matches!(nested, syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident(kind))
+        })
+}
+
+// Represents the options of a field's index, given by the arguments after the index kind, eg.
+//   `#[multi_index(ordered_non_unique, filter = "Self::is_open", filter_fields(status))]`.
+#[derive(Default)]
+pub(crate) struct FieldOptions {
+    pub(crate) filter: Option<Filter>,
+    // Whether the index is `sparse`, ie. on an `Option<T>` field, keyed on the inner value of the elements
+    //   where the field is `Some`.
+    pub(crate) sparse: bool,
+    // The custom equivalence of a hashed index, given by `eq = "..."`, either a type implementing KeyEquivalence
+    //   or the name of a builtin equivalence, eg. `ascii_case_insensitive`.
+    pub(crate) eq: Option<Type>,
+    // Whether an ordered index is in descending order, given by `order = "desc"`.
+    pub(crate) desc: bool,
+    // The function comparing the keys of an ordered index, given by `compare = "path::to::cmp"`.
+    pub(crate) compare: Option<Path>,
+}
+
+// Get the options of a field's index, see FieldOptions.
+pub(crate) fn get_field_options(f: &Field) -> FieldOptions {
+    let Some(attr) = f.attrs.iter().find(|attr| attr.path.is_ident("multi_index")) else {
+        return FieldOptions::default();
+    };
+    let Ok(syn::Meta::List(meta_list)) = attr.parse_meta() else {
+        return FieldOptions::default();
+    };
+
+    let mut sparse = false;
+    let mut eq = None;
+    let mut desc = false;
+    let mut compare = None;
+    let mut filter_args = FilterArgs::default();
+    for arg in meta_list.nested.iter().skip(1) {
+        match arg {
+            syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("sparse") => sparse = true,
+            syn::NestedMeta::Meta(syn::Meta::NameValue(name_value)) if name_value.path.is_ident("eq") => {
+                match &name_value.lit {
+                    syn::Lit::Str(lit) if lit.value() == "ascii_case_insensitive" => {
+                        eq = Some(syn::parse_quote!(::multi_index_map::AsciiCaseInsensitive));
                     }
+                    syn::Lit::Str(lit) => eq = parse_lit(lit),
+                    lit => emit_error!(lit.span(), "Invalid multi_index argument, expected a string literal"),
+                }
+            }
+            syn::NestedMeta::Meta(syn::Meta::NameValue(name_value)) if name_value.path.is_ident("order") => {
+                match &name_value.lit {
+                    syn::Lit::Str(lit) if lit.value() == "asc" => desc = false,
+                    syn::Lit::Str(lit) if lit.value() == "desc" => desc = true,
+                    lit => emit_error!(lit.span(), "Invalid multi_index order, should be one of [\"asc\", \"desc\"]"),
                 }
             }
+            syn::NestedMeta::Meta(syn::Meta::NameValue(name_value)) if name_value.path.is_ident("compare") => {
+                match &name_value.lit {
+                    syn::Lit::Str(lit) => compare = parse_lit(lit),
+                    lit => emit_error!(lit.span(), "Invalid multi_index argument, expected a string literal"),
+                }
+            }
+            _ if filter_args.parse_arg(arg) => {}
+            _ => emit_error!(arg.span(), "Invalid multi_index argument, should be one of [filter, filter_fields, sparse, eq, order, compare]"),
+        }
+    }
+    FieldOptions {
+        filter: filter_args.finish(&meta_list),
+        sparse,
+        eq,
+        desc,
+        compare,
+    }
+}
+
+// Get the inner type `T` of an `Option<T>`, or None if the type is not spelled as an Option.
+pub(crate) fn option_inner_type(ty: &Type) -> Option<&Type> {
+    match single_type_argument(ty)? {
+        (ident, inner) if ident == "Option" => Some(inner),
+        _ => None,
+    }
+}
+
+// Get the item type `T` of a collection such as `Vec<T>`, `BTreeSet<T>` or `[T; N]`, or None if the type is not
+//   spelled as a collection with a single type argument.
+pub(crate) fn collection_item_type(ty: &Type) -> Option<&Type> {
+    match ty {
+        syn::Type::Array(array) => Some(&array.elem),
+        _ => match single_type_argument(ty)? {
+            (ident, _) if ident == "Option" => None,
+            (_, item) => Some(item),
+        },
+    }
+}
+
+// Get the name of a path type with a single type argument, eg. `Option` and `T` for `Option<T>`.
+fn single_type_argument(ty: &Type) -> Option<(&Ident, &Type)> {
+    let syn::Type::Path(type_path) = ty else { return None };
+    let segment = type_path.path.segments.last()?;
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else { return None };
+    match args.args.first()? {
+        syn::GenericArgument::Type(inner) if args.args.len() == 1 => Some((&segment.ident, inner)),
+        _ => None,
+    }
+}
+
+// Map the first ident of a multi_index attribute to an Ordering and Uniqueness,
+//   emitting an error if it is not a recognised index kind.
+// `ordered_ranked` is an ordered non-unique index, see is_ranked, and likewise `hashed_multi` and `ordered_multi`
+//   are non-unique indexes, see is_multi.
+fn parse_index_kind(path: &Path) -> Option<(Ordering, Uniqueness)> {
+    match path.get_ident().map(|i| i.to_string()).as_deref() {
+        Some("hashed_unique") => Some((Ordering::Hashed, Uniqueness::Unique)),
+        Some("ordered_unique") => Some((Ordering::Ordered, Uniqueness::Unique)),
+        Some("hashed_non_unique") | Some("hashed_multi") => Some((Ordering::Hashed, Uniqueness::NonUnique)),
+        Some("ordered_non_unique") | Some("ordered_ranked") | Some("ordered_multi") => {
+            Some((Ordering::Ordered, Uniqueness::NonUnique))
+        }
+        _ => {
+            emit_error!(path.span(), "Invalid multi_index attribute, should be one of [hashed_unique, ordered_unique, hashed_non_unique, ordered_non_unique, ordered_ranked, hashed_multi, ordered_multi]");
+            None
+        }
+    }
+}
+
+// Represents a struct-level index over several fields at once, eg.
+//   `#[multi_index(hashed_unique, fields(account_id, symbol))]`.
+// The lookup table is keyed on a tuple of the listed fields, in the order they are listed.
+// With a computed key, eg.
+//   `#[multi_index(hashed_non_unique, key = "Self::norm", name = "norm", ty = "String", fields(name))]`,
+//   the lookup table is instead keyed on the result of the key function, and `fields` lists the fields the key
+//   is computed from, which must be given explicitly.
+pub(crate) struct CompositeIndex {
+    pub(crate) fields: Vec<Ident>,
+    pub(crate) ordering: Ordering,
+    pub(crate) uniqueness: Uniqueness,
+    pub(crate) ranked: bool,
+    pub(crate) computed: Option<ComputedKey>,
+    pub(crate) filter: Option<Filter>,
+}
+
+pub(crate) struct ComputedKey {
+    // Path to the function computing the key from a reference to the element.
+    pub(crate) key: Path,
+    // Name of the index, used in place of a field name for the generated accessors.
+    pub(crate) name: Ident,
+    // Type returned by the key function, which cannot be inferred by the derive macro.
+    pub(crate) ty: Type,
+}
+
+// Represents the predicate of a partial index, eg. `filter = "Self::is_open"`.
+// Only elements matching the predicate are added to the lookup table.
+pub(crate) struct Filter {
+    pub(crate) predicate: Path,
+    // The fields the predicate reads, given by `filter_fields(...)`.
+    pub(crate) fields: Vec<Ident>,
+}
+
+// Accumulates the filter and filter_fields arguments of a multi_index attribute, which may be given in any order.
+#[derive(Default)]
+struct FilterArgs {
+    predicate: Option<Path>,
+    fields: Option<Vec<Ident>>,
+}
+
+impl FilterArgs {
+    // Parse a single argument of a multi_index attribute, returning whether it was a filter argument.
+    fn parse_arg(&mut self, arg: &NestedMeta) -> bool {
+        match arg {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(name_value)) if name_value.path.is_ident("filter") => {
+                match &name_value.lit {
+                    syn::Lit::Str(lit) => self.predicate = parse_lit(lit),
+                    lit => emit_error!(lit.span(), "Invalid multi_index argument, expected a string literal"),
+                }
+                true
+            }
+            syn::NestedMeta::Meta(syn::Meta::List(list)) if list.path.is_ident("filter_fields") => {
+                self.fields = Some(parse_field_list(list));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn finish(self, meta_list: &MetaList) -> Option<Filter> {
+        match self {
+            FilterArgs { predicate: Some(predicate), fields: Some(fields) } => Some(Filter { predicate, fields }),
+            // The fields the predicate reads must be listed, as they are not treated as unindexed fields.
+            FilterArgs { predicate: Some(_), fields: None } => {
+                emit_error!(meta_list.span(), "Invalid multi_index attribute, filter requires filter_fields listing the fields the predicate reads, eg. filter_fields(status)");
+                None
+            }
+            FilterArgs { predicate: None, fields: Some(_) } => {
+                emit_error!(meta_list.span(), "Invalid multi_index attribute, filter_fields requires a filter");
+                None
+            }
+            FilterArgs { predicate: None, fields: None } => None,
+        }
+    }
+}
+
+// Parse a list of field names, eg. `fields(a, b)`.
+fn parse_field_list(list: &MetaList) -> Vec<Ident> {
+    let mut fields = Vec::new();
+    for field in &list.nested {
+        let syn::NestedMeta::Meta(syn::Meta::Path(field_path)) = field else {
+            emit_error!(field.span(), "Invalid multi_index fields, should be a list of field names");
+            continue;
+        };
+        let Some(field_ident) = field_path.get_ident() else {
+            emit_error!(field.span(), "Invalid multi_index fields, should be a list of field names");
+            continue;
+        };
+        fields.push(field_ident.clone());
+    }
+    fields
+}
+
+// Get every composite index declared with a struct-level multi_index attribute.
+pub(crate) fn get_composite_indexes(input: &DeriveInput) -> Vec<CompositeIndex> {
+    let mut composites = Vec::new();
+
+    for attr in &input.attrs {
+        if !attr.path.is_ident("multi_index") {
+            continue;
+        }
+
+        let Ok(syn::Meta::List(meta_list)) = attr.parse_meta() else {
+            emit_error!(attr.span(), "Invalid multi_index attribute, expected eg. #[multi_index(hashed_unique, fields(a, b))]");
+            continue;
+        };
+
+        let mut nested = meta_list.nested.iter();
+        let Some(syn::NestedMeta::Meta(syn::Meta::Path(kind_path))) = nested.next() else {
+            emit_error!(meta_list.span(), "Invalid multi_index attribute, the first argument should be the index kind");
+            continue;
+        };
+        // The sequenced and random_access indexes are not keyed on any fields,
+        //   so are handled with the other extra attributes.
+        if kind_path.is_ident("sequenced") || kind_path.is_ident("random_access") {
+            continue;
+        }
+        let Some((ordering, uniqueness)) = parse_index_kind(kind_path) else {
+            continue;
+        };
+        if kind_path.is_ident("hashed_multi") || kind_path.is_ident("ordered_multi") {
+            emit_error!(kind_path.span(), "Multi-valued multi_index attributes must be declared on a collection field");
+            continue;
+        }
+
+        let mut fields = Vec::new();
+        let mut key = None;
+        let mut name = None;
+        let mut ty = None;
+        let mut filter_args = FilterArgs::default();
+        for arg in nested {
+            if filter_args.parse_arg(arg) {
+                continue;
+            }
+            match arg {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(name_value)) => {
+                    let syn::Lit::Str(lit) = &name_value.lit else {
+                        emit_error!(name_value.lit.span(), "Invalid multi_index argument, expected a string literal");
+                        continue;
+                    };
+                    if name_value.path.is_ident("key") {
+                        key = parse_lit(lit);
+                    } else if name_value.path.is_ident("name") {
+                        name = parse_lit(lit);
+                    } else if name_value.path.is_ident("ty") {
+                        ty = parse_lit(lit);
+                    } else {
+                        emit_error!(name_value.path.span(), "Invalid multi_index argument, should be one of [fields, key, name, ty, filter, filter_fields]");
+                    }
+                }
+                syn::NestedMeta::Meta(syn::Meta::List(list)) if list.path.is_ident("fields") => {
+                    fields.extend(parse_field_list(list));
+                }
+                _ => emit_error!(arg.span(), "Invalid multi_index argument, expected fields(...)"),
+            }
+        }
+
+        let computed = match (key, name, ty) {
+            (None, None, None) => None,
+            (Some(key), Some(name), Some(ty)) => Some(ComputedKey { key, name, ty }),
+            _ => {
+                emit_error!(meta_list.span(), "Computed key multi_index attribute must have all of key, name and ty, eg. key = \"Self::norm\", name = \"norm\", ty = \"String\"");
+                continue;
+            }
+        };
+
+        // The fields a computed key is read from must be listed, as they are not treated as unindexed fields.
+        if computed.is_some() {
+            if fields.is_empty() {
+                emit_error!(meta_list.span(), "Computed key multi_index attribute must list the fields the key is computed from, eg. fields(name)");
+                continue;
+            }
+        } else if fields.len() < 2 {
+            emit_error!(meta_list.span(), "Composite multi_index attribute must list at least two fields, eg. fields(a, b)");
+            continue;
+        }
+
+        composites.push(CompositeIndex {
+            fields,
+            ordering,
+            uniqueness,
+            ranked: kind_path.is_ident("ordered_ranked"),
+            computed,
+            filter: filter_args.finish(&meta_list),
+        });
+    }
+
+    composites
+}
+
+// Parse the contents of a string literal argument, emitting an error if it is not valid.
+fn parse_lit<T: syn::parse::Parse>(lit: &syn::LitStr) -> Option<T> {
+    match lit.parse() {
+        Ok(parsed) => Some(parsed),
+        Err(err) => {
+            emit_error!(lit.span(), "Invalid multi_index argument, {}", err);
+            None
         }
     }
-    None
 }
 
 pub(crate) struct ExtraAttributes {
     pub(crate) derives: Vec<Meta>,
     pub(crate) hasher: syn::Path,
+    // Set by `#[multi_index_handles(generational)]`, so that key handles carry a generation counter
+    //   and can be detected as stale once the element they refer to has been removed.
+    pub(crate) generational_handles: bool,
+    // Set by the struct-level `#[multi_index(sequenced)]`, so that the map keeps its elements in a doubly linked
+    //   sequence, in insertion order unless elements are relocated.
+    pub(crate) sequenced: bool,
+    // Set by the struct-level `#[multi_index(random_access)]`, so that the map keeps the positions of its elements
+    //   in a dense sequence, and elements can be accessed by their offset in that sequence.
+    pub(crate) random_access: bool,
 }
 
 impl Default for ExtraAttributes {
     fn default() -> Self {
         Self {
             derives: Default::default(),
+            generational_handles: false,
+            sequenced: false,
+            random_access: false,
             #[cfg(feature = "rustc-hash")]
             hasher: syn::parse_quote!(::multi_index_map::rustc_hash::FxBuildHasher),
             #[cfg(not(feature = "rustc-hash"))]
@@ -126,6 +474,49 @@ pub(crate) fn get_extra_attributes(f: &DeriveInput) -> ExtraAttributes {
                 break;
             }
         }
+
+        // Any other struct-level multi_index attribute is a composite index, see get_composite_indexes.
+        if attr.path.is_ident("multi_index") {
+            let Ok(syn::Meta::List(meta_list)) = attr.parse_meta() else {
+                continue;
+            };
+            let Some(syn::NestedMeta::Meta(syn::Meta::Path(kind_path))) = meta_list.nested.first() else {
+                continue;
+            };
+            let kind = if kind_path.is_ident("sequenced") {
+                &mut extra_attrs.sequenced
+            } else if kind_path.is_ident("random_access") {
+                &mut extra_attrs.random_access
+            } else {
+                continue;
+            };
+            *kind = true;
+            if meta_list.nested.len() > 1 {
+                emit_error!(
+                    meta_list.span(),
+                    "Invalid multi_index attribute, sequenced and random_access do not take any fields"
+                );
+            }
+        }
+
+        if attr.path.is_ident("multi_index_handles") {
+            let Ok(syn::Meta::List(meta_list)) = attr.parse_meta() else {
+                break
+            };
+            for nested in &meta_list.nested {
+                match nested {
+                    syn::NestedMeta::Meta(syn::Meta::Path(nested_path))
+                        if nested_path.is_ident("generational") =>
+                    {
+                        extra_attrs.generational_handles = true;
+                    }
+                    _ => emit_error!(
+                        nested.span(),
+                        "Invalid multi_index_handles attribute, should be one of [generational]"
+                    ),
+                }
+            }
+        }
     }
 
     extra_attrs
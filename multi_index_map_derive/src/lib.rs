@@ -1,17 +1,16 @@
-use ::proc_macro_error2::{abort_call_site, proc_macro_error};
+use ::proc_macro_error2::{abort_call_site, emit_error, proc_macro_error};
 use ::quote::format_ident;
 use ::syn::{parse_macro_input, DeriveInput};
-use convert_case::Casing;
 use generators::{generate_iter_mut, FieldIdents, EXPECT_NAMED_FIELDS};
 use proc_macro_error2::OptionExt;
-use syn::parse_quote;
+use syn::{parse_quote, spanned::Spanned};
 
 mod generators;
 mod index_attributes;
 
 #[proc_macro_derive(
     MultiIndexMap,
-    attributes(multi_index, multi_index_derive, multi_index_hash)
+    attributes(multi_index, multi_index_derive, multi_index_hash, multi_index_handles)
 )]
 #[proc_macro_error]
 pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -20,6 +19,8 @@ pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
 
     let extra_attrs = index_attributes::get_extra_attributes(&input);
 
+    let composite_indexes = index_attributes::get_composite_indexes(&input);
+
     // Extract the struct fields if we are parsing a struct,
     // otherwise throw an error as we do not support Enums or Unions.
     let fields = match input.data {
@@ -35,6 +36,13 @@ pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
         )
     };
 
+    // Keep the type of every field, so composite indexes can build their tuple key type.
+    let field_types = named_fields
+        .named
+        .iter()
+        .filter_map(|f| Some((f.ident.clone()?, f.ty.clone())))
+        .collect::<Vec<_>>();
+
     // Filter out all the fields that do not have a multi_index attribute,
     // so we can ignore the non-indexed fields.
     let (indexed_fields, unindexed_fields): (Vec<_>, Vec<_>) = named_fields
@@ -50,34 +58,186 @@ pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
 
     let map_name = format_ident!("MultiIndex{}Map", element_name);
 
+    // The fields read by the filter of a partial index must be fields of the element.
+    let filter_idents = |filter: index_attributes::Filter| {
+        let source_fields = filter.fields;
+        for source_field in &source_fields {
+            if !field_types.iter().any(|(ident, _)| ident == source_field) {
+                emit_error!(source_field.span(), "Unknown field in multi_index filter_fields");
+            }
+        }
+
+        generators::FilterIdents {
+            filter_fn: generators::resolve_fn_path(&filter.predicate, element_name, &input.generics),
+            source_fields,
+        }
+    };
+
+    // The marker types of indexes with a compare function, see generators::generate_comparator.
+    let mut comparators = Vec::new();
+
     // Massage the two partitioned Vecs into the correct types
     let indexed_fields = indexed_fields
         .into_iter()
-        .map(|(field, kind)| {
+        .map(|(mut field, kind)| {
             let (ordering, uniqueness) = kind
                 .expect_or_abort("Internal logic broken, all indexed fields should have a kind");
 
-            let field_ident = field.ident.as_ref().expect_or_abort(EXPECT_NAMED_FIELDS);
+            let field_ident = field.ident.clone().expect_or_abort(EXPECT_NAMED_FIELDS);
+            let options = index_attributes::get_field_options(&field);
 
-            let idents = FieldIdents {
-                name: field_ident.clone(),
-                index_name: format_ident!("_{field_ident}_index",),
-                cloned_name: format_ident!("{field_ident}_orig",),
-                iter_name: format_ident!(
-                    "{map_name}{}Iter",
-                    field_ident
-                        .to_string()
-                        .to_case(::convert_case::Case::UpperCamel),
-                ),
+            let multi = index_attributes::is_multi(&field);
+            let mut idents = FieldIdents {
+                filter: options.filter.map(filter_idents),
+                sparse: options.sparse,
+                multi: multi.then(|| field.ty.clone()),
+                ..FieldIdents::new(
+                    &map_name,
+                    &field_ident,
+                    vec![field_ident.clone()],
+                    index_attributes::is_ranked(&field),
+                )
+            };
+
+            // A sparse index is keyed on the inner type of the Option, so the lookup table and accessors use it
+            //   in place of the field's own type.
+            if options.sparse {
+                match index_attributes::option_inner_type(&field.ty) {
+                    Some(inner) => field.ty = inner.clone(),
+                    None => emit_error!(field.ty.span(), "A sparse multi_index field must have type Option<T>"),
+                }
+            }
+
+            // Likewise a multi-valued index is keyed on the item type of the collection.
+            if multi {
+                if options.sparse {
+                    emit_error!(field.ty.span(), "A multi-valued multi_index field cannot be sparse");
+                }
+                match index_attributes::collection_item_type(&field.ty) {
+                    Some(item) => field.ty = item.clone(),
+                    None => emit_error!(field.ty.span(), "A multi-valued multi_index field must be a collection, eg. Vec<T>"),
+                }
+            }
+
+            // A custom equivalence or order wraps the keys of the lookup table, see generators::KeyWrapper.
+            // A compare function is called through a marker type generated alongside the map.
+            let order = match (options.compare, options.desc) {
+                (Some(compare), desc) => {
+                    let marker = idents.comparator_name.clone();
+                    let compare_fn = generators::resolve_fn_path(&compare, element_name, &input.generics);
+                    comparators.push(generators::generate_comparator(
+                        &marker,
+                        &compare_fn,
+                        desc,
+                        &field.ty,
+                        &input.vis,
+                        &input.generics,
+                    ));
+                    let (_, types, _) = input.generics.split_for_impl();
+                    Some(parse_quote!(#marker #types))
+                }
+                (None, true) => Some(parse_quote!(::multi_index_map::Descending)),
+                (None, false) => None,
+            };
+            idents.key_wrapper = match (options.eq, order, &ordering) {
+                (None, None, _) => None,
+                (Some(eq), None, index_attributes::Ordering::Hashed) => Some(generators::KeyWrapper {
+                    path: parse_quote!(::multi_index_map::Equivalent),
+                    marker: eq,
+                    reversed: false,
+                }),
+                (None, Some(order), index_attributes::Ordering::Ordered) => Some(generators::KeyWrapper {
+                    path: parse_quote!(::multi_index_map::Compared),
+                    marker: order,
+                    reversed: options.desc,
+                }),
+                (Some(_), _, _) => {
+                    emit_error!(field_ident.span(), "A custom eq is only supported on hashed multi_index fields");
+                    None
+                }
+                (None, Some(_), _) => {
+                    emit_error!(field_ident.span(), "A custom order or compare is only supported on ordered multi_index fields");
+                    None
+                }
             };
+            if idents.key_wrapper.is_some() && multi {
+                emit_error!(field_ident.span(), "A multi-valued multi_index field cannot have a custom eq or order");
+            }
 
             (field, idents, ordering, uniqueness)
         })
+        .chain(composite_indexes.into_iter().filter_map(|composite| {
+            // A composite index is treated as an indexed pseudo-field, named after its key fields joined
+            //   with underscores, whose type is the tuple of the key field types.
+            let mut key_types = Vec::with_capacity(composite.fields.len());
+            for key_field in &composite.fields {
+                let Some((_, ty)) = field_types.iter().find(|(ident, _)| ident == key_field) else {
+                    emit_error!(key_field.span(), "Unknown field in composite multi_index attribute");
+                    return None;
+                };
+                key_types.push(ty);
+            }
+
+            // A computed key index is likewise treated as an indexed pseudo-field, given the name and type
+            //   from the attribute, whose key fields are the fields the key is computed from.
+            if let Some(computed) = composite.computed {
+                let key_fn = generators::resolve_fn_path(&computed.key, element_name, &input.generics);
+                let field = syn::Field {
+                    attrs: Vec::new(),
+                    vis: input.vis.clone(),
+                    ident: Some(computed.name.clone()),
+                    colon_token: None,
+                    ty: computed.ty,
+                };
+
+                let idents = FieldIdents {
+                    filter: composite.filter.map(filter_idents),
+                    computed: Some(generators::ComputedKeyIdents { key_fn }),
+                    ..FieldIdents::new(&map_name, &computed.name, composite.fields, composite.ranked)
+                };
+
+                return Some((field, idents, composite.ordering, composite.uniqueness));
+            }
+
+            let name = composite
+                .fields
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("_");
+            let field_ident = format_ident!("{name}");
+
+            let field = syn::Field {
+                attrs: Vec::new(),
+                vis: input.vis.clone(),
+                ident: Some(field_ident.clone()),
+                colon_token: None,
+                ty: parse_quote!((#(#key_types,)*)),
+            };
+
+            let idents = FieldIdents {
+                filter: composite.filter.map(filter_idents),
+                ..FieldIdents::new(&map_name, &field_ident, composite.fields, composite.ranked)
+            };
+
+            Some((field, idents, composite.ordering, composite.uniqueness))
+        }))
         .collect::<Vec<_>>();
 
+    // Fields which are part of a composite index are keys of that index, so must not be mutable through
+    //   the accessors of unindexed fields, even if they have no index of their own.
+    // Likewise for the fields a computed key is computed from, and the fields the filter of a partial index reads.
+    let read_fields = indexed_fields
+        .iter()
+        .flat_map(|(_field, idents, _ordering, _uniqueness)| {
+            let filter_fields = idents.filter.iter().flat_map(|filter| &filter.source_fields);
+            idents.key_fields.iter().chain(filter_fields).cloned().map(Some)
+        })
+        .collect::<Vec<_>>();
     let unindexed_fields = unindexed_fields
         .into_iter()
         .map(|(field, _)| field)
+        .filter(|field| !read_fields.contains(&field.ident))
         .collect::<Vec<_>>();
 
     let lookup_table_fields = generators::generate_lookup_tables(&indexed_fields, &extra_attrs);
@@ -90,17 +250,28 @@ pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
 
     let lookup_table_fields_shrink = generators::generate_lookup_table_shrink(&indexed_fields);
 
-    let entries_for_insert = generators::generate_entries_for_insert(&indexed_fields);
+    let key_name = format_ident!("{map_name}Key");
+    let occupied_entry_name = format_ident!("{map_name}OccupiedEntry");
+    let matches_name = format_ident!("{map_name}Matches");
+    let existing_handle =
+        generators::key_handle(&key_name, &extra_attrs, &format_ident!("existing"));
+
+    let entries_for_insert =
+        generators::generate_entries_for_insert(&indexed_fields, &existing_handle);
 
     let inserts_for_entries = generators::generate_inserts_for_entries(&indexed_fields);
 
-    let removes = generators::generate_removes(&indexed_fields);
+    let removes = generators::generate_removes(&indexed_fields, &extra_attrs);
 
     let pre_modifies = generators::generate_pre_modifies(&indexed_fields);
 
     let post_modifies = generators::generate_post_modifies(&indexed_fields);
 
-    let clears = generators::generate_clears(&indexed_fields);
+    let uniqueness_checks = generators::generate_uniqueness_checks(&indexed_fields);
+
+    let unique_lookups = generators::generate_unique_lookups(&indexed_fields);
+
+    let clears = generators::generate_clears(&indexed_fields).collect::<Vec<_>>();
 
     let unindexed_types = unindexed_fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
     let unindexed_idents = unindexed_fields
@@ -120,6 +291,97 @@ pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
         &removes,
         &pre_modifies,
         &post_modifies,
+        &uniqueness_checks,
+        &unique_lookups,
+        &occupied_entry_name,
+        &matches_name,
+        &key_name,
+        &existing_handle,
+        &input.generics,
+        &iter_generics,
+    );
+
+    let key_accessors = generators::generate_key_accessors(
+        &key_name,
+        &extra_attrs,
+        element_name,
+        &input.vis,
+        &unindexed_types,
+        &unindexed_idents,
+        &removes,
+        &pre_modifies,
+        &post_modifies,
+        &input.generics,
+    );
+
+    let insert_or_replace = generators::generate_insert_or_replace(
+        element_name,
+        &input.vis,
+        &removes,
+        &unique_lookups,
+        &input.generics,
+    );
+
+    let extract_if_name = format_ident!("{map_name}ExtractIf");
+    let retain = generators::generate_retain(
+        &extract_if_name,
+        element_name,
+        &input.vis,
+        &removes,
+        &input.generics,
+        &iter_generics,
+    );
+    let extract_if = generators::generate_extract_if(
+        &extract_if_name,
+        &map_name,
+        element_name,
+        &input.vis,
+        &input.generics,
+        &iter_generics,
+    );
+
+    let matches = generators::generate_matches(
+        &matches_name,
+        element_name,
+        &input.vis,
+        &input.generics,
+        &iter_generics,
+    );
+
+    let sequenced_name = format_ident!("{map_name}Sequenced");
+    let sequenced_accessors = generators::generate_sequenced_accessors(
+        &extra_attrs,
+        &sequenced_name,
+        &key_name,
+        element_name,
+        &input.vis,
+        &input.generics,
+        &iter_generics,
+    );
+    let sequenced_iter = generators::generate_sequenced_iter(
+        &extra_attrs,
+        &sequenced_name,
+        element_name,
+        &input.vis,
+        &input.generics,
+        &iter_generics,
+    );
+
+    let random_access_name = format_ident!("{map_name}RandomAccess");
+    let random_access_accessors = generators::generate_random_access_accessors(
+        &extra_attrs,
+        &random_access_name,
+        &key_name,
+        element_name,
+        &input.vis,
+        &input.generics,
+        &iter_generics,
+    );
+    let random_access_iter = generators::generate_random_access_iter(
+        &extra_attrs,
+        &random_access_name,
+        element_name,
+        &input.vis,
         &input.generics,
         &iter_generics,
     );
@@ -127,6 +389,21 @@ pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
     let iterators = generators::generate_iterators(
         &indexed_fields,
         element_name,
+        &matches_name,
+        &input.generics,
+        &iter_generics,
+    );
+
+    let entries = generators::generate_entries(
+        &indexed_fields,
+        &extra_attrs,
+        &map_name,
+        &occupied_entry_name,
+        &key_name,
+        &existing_handle,
+        element_name,
+        &input.vis,
+        &unindexed_types,
         &input.generics,
         &iter_generics,
     );
@@ -154,7 +431,7 @@ pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
         inserts_for_entries,
         accessors,
         iterators,
-        clears,
+        &clears,
         lookup_table_fields,
         lookup_table_fields_init,
         lookup_table_fields_default,
@@ -163,6 +440,18 @@ pub fn multi_index_map(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
         &iter_mut_name,
         iter_mut,
         &iter_generics,
+        &key_name,
+        key_accessors,
+        insert_or_replace,
+        entries,
+        retain,
+        extract_if,
+        matches,
+        sequenced_accessors,
+        sequenced_iter,
+        random_access_accessors,
+        random_access_iter,
+        &comparators,
     );
 
     // Hand the output tokens back to the compiler.